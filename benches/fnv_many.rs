@@ -0,0 +1,35 @@
+use rbin::*;
+use std::time::Instant;
+
+const ROUNDS: usize = 200;
+
+fn names() -> Vec<String> {
+    let mut names = Vec::new();
+    for character in 0..200 {
+        names.push(format!("Characters/Hero{}", character));
+        for skin in 0..20 {
+            names.push(format!("Characters/Hero{}/Skins/Skin{}", character, skin));
+            names.push(format!("Characters/Hero{}/Skins/Skin{}/Particles/Hero{}_Base_Q_Mis", character, skin, character));
+        }
+    }
+    names
+}
+
+fn measure<B: FnvBackend>(label: &str, backend: &B, names: &[String]) -> Vec<u32> {
+    let start = Instant::now();
+    let mut hashes = Vec::new();
+    for _ in 0..ROUNDS {
+        hashes = backend.fnv_many(names);
+    }
+    let seconds = start.elapsed().as_secs_f64();
+    let bytes: usize = names.iter().map(String::len).sum::<usize>() * ROUNDS;
+    println!("{}: {:.3}s, {:.2} MB/s", label, seconds, bytes as f64 / seconds.max(f64::EPSILON) / 1_000_000.0);
+    hashes
+}
+
+fn main() {
+    let names = names();
+    let scalar = measure("scalar", &ScalarFnvBackend, &names);
+    let lanes = measure("lanes", &LanesFnvBackend, &names);
+    assert_eq!(scalar, lanes);
+}
@@ -0,0 +1,68 @@
+use rbin::*;
+use std::env;
+use std::fs;
+
+fn entry_label(name: &BinFNV) -> String {
+    if name.get_string().is_empty() {
+        format!("0x{:08X}", name.get_hash())
+    } else {
+        name.get_string().to_string()
+    }
+}
+
+fn read_bin(path: &str, hashes: &BinHashes) -> Bin {
+    let data = fs::read(path).expect("Failed to read file!");
+    Bin::read_from_data(&data, hashes).expect("Failed to read bin!")
+}
+
+fn print_diff(diff: &BinDiff) {
+    for name in &diff.removed {
+        println!("- {}", entry_label(name));
+    }
+    for name in &diff.added {
+        println!("+ {}", entry_label(name));
+    }
+    for (old, new) in &diff.renamed {
+        println!("> {} -> {}", entry_label(old), entry_label(new));
+    }
+    for entry in &diff.changed {
+        println!("~ {}", entry_label(&entry.name));
+        for change in &entry.changes {
+            match (&change.old, &change.new) {
+                (Some(old), Some(new)) => println!("    {}: {:?} -> {:?}", change.path, old, new),
+                (Some(old), None) => println!("    {}: removed {:?}", change.path, old),
+                (None, Some(new)) => println!("    {}: added {:?}", change.path, new),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+fn main() {
+    let mut paths = Vec::new();
+    let mut format = "text".to_string();
+    let mut hashes_dir = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next().expect("Missing format"),
+            "--hashes-dir" => hashes_dir = Some(args.next().expect("Missing hashes directory")),
+            _ => paths.push(arg),
+        }
+    }
+    if paths.len() != 2 {
+        panic!("Usage: rbindiff [--format text|json] [--hashes-dir DIR] old.bin new.bin");
+    }
+    let hashes_dir = hashes_dir
+        .or_else(|| env::var("RBIN_HASHES_DIR").ok())
+        .unwrap_or_else(|| "hashes".to_string());
+    let hashes = BinHashes::load_from_dir(hashes_dir).expect("Failed to read hashes!");
+    let old = read_bin(&paths[0], &hashes);
+    let new = read_bin(&paths[1], &hashes);
+    let diff = old.diff(&new);
+    match format.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&diff.to_json()).unwrap()),
+        "text" => print_diff(&diff),
+        _ => panic!("Unknown format {}", format),
+    }
+}
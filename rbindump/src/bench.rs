@@ -0,0 +1,92 @@
+use crate::{collect_bins, dump_bin, DumpOptions};
+use rbin::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+#[derive(Default)]
+struct PhaseStats {
+    time: Duration,
+    allocations: usize,
+    bytes: usize,
+}
+
+fn measure<T, F: FnOnce() -> T>(stats: &mut PhaseStats, f: F) -> T {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f();
+    stats.time += start.elapsed();
+    stats.allocations += ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    result
+}
+
+pub fn bench(dir: &Path, hashes: &BinHashes, options: &DumpOptions, iterations: usize, render: bool) {
+    let mut paths = Vec::new();
+    collect_bins(dir, &mut paths);
+    let mut read = PhaseStats::default();
+    let files: Vec<Vec<u8>> = measure(&mut read, || {
+        paths.iter().map(|path| fs::read(path).expect("Failed to read file!")).collect()
+    });
+    read.bytes = files.iter().map(Vec::len).sum();
+    let mut parse = PhaseStats::default();
+    let mut dump = PhaseStats::default();
+    let mut failed = 0;
+    for _ in 0..iterations {
+        let bins: Vec<Bin> = measure(&mut parse, || {
+            files
+                .iter()
+                .filter_map(|data| Bin::read_from_data(data, hashes).ok())
+                .collect()
+        });
+        parse.bytes += read.bytes;
+        failed = files.len() - bins.len();
+        if render {
+            measure(&mut dump, || {
+                for bin in bins {
+                    dump_bin(bin, hashes, options);
+                }
+            });
+            dump.bytes += read.bytes;
+        }
+    }
+    println!("files: {} ({} failed), bytes: {}, iterations: {}", files.len(), failed, read.bytes, iterations);
+    let mut phases = vec![("read", &read), ("parse", &parse)];
+    if render {
+        phases.push(("render", &dump));
+    }
+    for (name, stats) in phases {
+        let seconds = stats.time.as_secs_f64();
+        println!(
+            "{}: {:.3}s, {:.2} MB/s, {} allocations",
+            name,
+            seconds,
+            stats.bytes as f64 / seconds.max(f64::EPSILON) / 1_000_000.0,
+            stats.allocations
+        );
+    }
+}
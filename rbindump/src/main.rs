@@ -1,17 +1,994 @@
+use rayon::prelude::*;
 use rbin::*;
+use serde::ser::{Serialize, SerializeMap, Serializer};
 use std::env;
+use std::fmt::Write;
 use std::fs;
-use std::fs::File;
+use std::io::{self, Read};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "bench")]
+mod bench;
+mod repl;
+
+#[cfg(feature = "bench")]
+use bench::bench;
+
+const NESTED_DEPTH: usize = 8;
+const UNKNOWN_GUESSES: usize = 3;
+
+fn indent(text: &str, out: &mut String) {
+    for line in text.lines() {
+        out.push_str("        ");
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+fn parse_name(name: &str) -> u32 {
+    match name.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).expect("Bad hash"),
+        None => fnv(name),
+    }
+}
+
+fn parse_field_names(list: &str) -> Vec<u32> {
+    list.split(',')
+        .filter(|name| !name.is_empty())
+        .map(parse_name)
+        .collect()
+}
+
+fn exclude_fields(value: &mut BinValue, exclude: &[u32]) {
+    match value {
+        BinValue::List(_, items) | BinValue::List2(_, items) => {
+            for item in items {
+                exclude_fields(item, exclude);
+            }
+        }
+        BinValue::Option(_, Some(item)) => exclude_fields(item, exclude),
+        BinValue::Map(_, _, items) => {
+            for (_, value) in items {
+                exclude_fields(value, exclude);
+            }
+        }
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+            fields.retain(|name, _| !exclude.contains(&name.get_hash()));
+            for value in fields.values_mut() {
+                exclude_fields(value, exclude);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn project_bin(bin: &mut Bin, only: &[u32], exclude: &[u32]) {
+    for value in bin.entries.values_mut() {
+        if let BinValue::Struct(_, fields) | BinValue::Embed(_, fields) = value {
+            if !only.is_empty() {
+                fields.retain(|name, _| only.contains(&name.get_hash()));
+            }
+        }
+        exclude_fields(value, exclude);
+    }
+}
+
+fn collapse_curves(value: &mut BinValue) {
+    match value {
+        BinValue::List(_, items) | BinValue::List2(_, items) => {
+            for item in items {
+                collapse_curves(item);
+            }
+        }
+        BinValue::Option(_, Some(item)) => collapse_curves(item),
+        BinValue::Map(_, _, items) => {
+            for (_, value) in items {
+                collapse_curves(value);
+            }
+        }
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+            for value in fields.values_mut() {
+                collapse_curves(value);
+            }
+            let times_name = BinFNV::from_string("times");
+            let values_name = BinFNV::from_string("values");
+            let is_curve = match (fields.get(&times_name), fields.get(&values_name)) {
+                (Some(BinValue::List(_, times)), Some(BinValue::List(_, values))) => {
+                    times.len() == values.len()
+                        && times.iter().all(|time| matches!(time, BinValue::Float(_)))
+                }
+                _ => false,
+            };
+            if is_curve {
+                if let (Some(BinValue::List(_, times)), Some(BinValue::List(value_type, values))) =
+                    (fields.shift_remove(&times_name), fields.shift_remove(&values_name))
+                {
+                    let curve = times.into_iter().zip(values).collect();
+                    let name = BinFNV::from_hash_string(fnv("curve"), "curve");
+                    fields.insert(name, BinValue::Map(BinType::F32, value_type, curve));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn render_pretty(name: &BinFNV, value: &BinValue, out: &mut String) {
+    indent(&format!("{:#?}: {:#?},", name, value), out);
+}
+
+fn render_compact(name: &BinFNV, value: &BinValue, out: &mut String) {
+    writeln!(out, "{:?}: {:?}", name, value).unwrap();
+}
+
+fn render_entries(bin: &Bin, render: fn(&BinFNV, &BinValue, &mut String)) -> Vec<String> {
+    let entries: Vec<(&BinFNV, &BinValue)> = bin.entries.iter().collect();
+    entries
+        .par_iter()
+        .map(|(name, value)| {
+            let mut out = String::new();
+            render(name, value, &mut out);
+            out
+        })
+        .collect()
+}
+
+fn render_bin_compact(bin: &Bin) -> String {
+    render_entries(bin, render_compact).concat()
+}
+
+fn render_bin(bin: &Bin) -> String {
+    let chunks = render_entries(bin, render_pretty);
+    let mut out = String::new();
+    writeln!(out, "Bin {{").unwrap();
+    writeln!(out, "    is_patch: {:?},", bin.is_patch).unwrap();
+    writeln!(out, "    version: {:?},", bin.version).unwrap();
+    if bin.links.is_empty() {
+        writeln!(out, "    links: [],").unwrap();
+    } else {
+        writeln!(out, "    links: [").unwrap();
+        for link in &bin.links {
+            writeln!(out, "        {:?},", link).unwrap();
+        }
+        writeln!(out, "    ],").unwrap();
+    }
+    if bin.entries.is_empty() {
+        writeln!(out, "    entries: {{}},").unwrap();
+    } else {
+        writeln!(out, "    entries: {{").unwrap();
+        for chunk in chunks {
+            out.push_str(&chunk);
+        }
+        writeln!(out, "    }},").unwrap();
+    }
+    if bin.patches.is_empty() {
+        writeln!(out, "    patches: [],").unwrap();
+    } else {
+        writeln!(out, "    patches: [").unwrap();
+        for patch in &bin.patches {
+            indent(&format!("{:#?},", patch), &mut out);
+        }
+        writeln!(out, "    ],").unwrap();
+    }
+    if !bin.tail.is_empty() {
+        writeln!(out, "    tail: {} bytes,", bin.tail.len()).unwrap();
+    }
+    write!(out, "}}").unwrap();
+    out
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn emit(output: Option<&Path>, text: &str) {
+    match output {
+        Some(output) => fs::write(output, text).expect("Failed to write output!"),
+        None => io::Write::write_all(&mut io::stdout().lock(), text.as_bytes()).expect("Failed to write output!"),
+    }
+}
+
+fn format_rows(headers: &[&str], rows: &[Vec<String>], format: &str, separator: &str) -> String {
+    let mut out = String::new();
+    match format {
+        "json" => {
+            let rows: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|row| {
+                    let row = headers.iter().zip(row).map(|(header, field)| (header.to_string(), field.clone().into()));
+                    serde_json::Value::Object(row.collect())
+                })
+                .collect();
+            writeln!(out, "{}", serde_json::to_string_pretty(&rows).unwrap()).unwrap();
+        }
+        "csv" => {
+            writeln!(out, "{}", headers.join(",")).unwrap();
+            for row in rows {
+                let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+                writeln!(out, "{}", row.join(",")).unwrap();
+            }
+        }
+        "debug" | "text" => {
+            for row in rows {
+                writeln!(out, "{}", row.join(separator)).unwrap();
+            }
+        }
+        _ => panic!("Unknown format {}", format),
+    }
+    out
+}
+
+#[cfg(feature = "remote-hashes")]
+fn update_hashes_dir(dir: &str) {
+    download_hashes(CDTB_HASHES_URL, dir).expect("Failed to update hashes!");
+}
+
+#[cfg(not(feature = "remote-hashes"))]
+fn update_hashes_dir(_dir: &str) {
+    panic!("rbindump was built without the remote-hashes feature");
+}
+
+struct DumpOptions {
+    compact: bool,
+    format: String,
+    curves: bool,
+    only: Vec<u32>,
+    exclude: Vec<u32>,
+    quiet: bool,
+    sorted: bool,
+    guess_unknown: bool,
+    renames: Option<BinRenameMap>,
+}
+
+fn prepare_bin(bin: &mut Bin, options: &DumpOptions) {
+    if let Some(renames) = &options.renames {
+        bin.apply_renames(renames);
+    }
+    project_bin(bin, &options.only, &options.exclude);
+    if options.sorted {
+        bin.sort();
+    }
+    if options.curves {
+        for value in bin.entries.values_mut() {
+            collapse_curves(value);
+        }
+    }
+}
+
+fn dump_bin(mut bin: Bin, hashes: &BinHashes, options: &DumpOptions) -> String {
+    prepare_bin(&mut bin, options);
+    let mut text = match options.format.as_str() {
+        "text" => bin.to_text(hashes),
+        "json" => return bin.write_to_json(),
+        "yaml" => return bin.to_yaml(),
+        "debug" if options.compact => render_bin_compact(&bin),
+        "debug" => render_bin(&bin),
+        _ => panic!("Unknown format {}", options.format),
+    };
+    if options.guess_unknown {
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&unknown_guesses(&bin, hashes));
+    }
+    text
+}
+
+fn read_options(options: &DumpOptions) -> ReadOptions {
+    ReadOptions {
+        recover_unknown: options.guess_unknown,
+        ..ReadOptions::default()
+    }
+}
+
+fn sample_indices(count: usize, sample: usize, random: bool) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..count).collect();
+    if random {
+        let mut state = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0)
+            | 1;
+        for index in 0..sample.min(count) {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            indices.swap(index, index + (state % (count - index) as u64) as usize);
+        }
+    }
+    indices.truncate(sample);
+    indices.sort_unstable();
+    indices
+}
+
+fn partial_bin(reader: &BinLazyReader, names: &[BinFNV]) -> Bin {
+    let mut entries = IndexMap::new();
+    for name in names {
+        entries.insert(*name, reader.entry(name).expect("Failed to read entry!"));
+    }
+    Bin {
+        is_patch: reader.is_patch,
+        version: reader.version,
+        links: reader.links.clone(),
+        entries,
+        patches: Vec::new(),
+        tail: Vec::new(),
+        errors: Vec::new(),
+    }
+}
+
+fn sample_bin(data: &[u8], hashes: &BinHashes, options: &DumpOptions, sample: usize, random: bool) -> Bin {
+    let read_options = read_options(options);
+    let reader = BinLazyReader::new(data, hashes, &read_options).expect("Failed to read bin!");
+    let names: Vec<BinFNV> = sample_indices(reader.names().len(), sample, random)
+        .into_iter()
+        .map(|index| reader.names()[index])
+        .collect();
+    partial_bin(&reader, &names)
+}
+
+fn extract_entries(data: &[u8], hashes: &BinHashes, options: &DumpOptions, queries: &[String]) -> Bin {
+    let wanted: Vec<u32> = queries.iter().map(|name| parse_name(name)).collect();
+    let read_options = read_options(options);
+    let reader = BinLazyReader::new(data, hashes, &read_options).expect("Failed to read bin!");
+    let names: Vec<BinFNV> = reader
+        .names()
+        .iter()
+        .filter(|name| wanted.contains(&name.get_hash()))
+        .copied()
+        .collect();
+    if !options.quiet {
+        for (query, hash) in queries.iter().zip(&wanted) {
+            if !names.iter().any(|name| name.get_hash() == *hash) {
+                eprintln!("{}: not found", query);
+            }
+        }
+    }
+    partial_bin(&reader, &names)
+}
+
+fn unknown_guesses(bin: &Bin, hashes: &BinHashes) -> String {
+    let mut out = String::new();
+    bin.walk(|path, value| {
+        if let BinValue::Unknown(type_id, bytes, _) = value {
+            writeln!(out, "# {}: unknown type 0x{:02X}, {} bytes", path, type_id, bytes.len()).unwrap();
+            for guess in value.guess_unknown(hashes).iter().take(UNKNOWN_GUESSES) {
+                writeln!(out, "#   {} ({} bytes): {:?}", guess.bin_type, guess.len, guess.value).unwrap();
+            }
+        }
+    });
+    out
+}
+
+fn output_extension(options: &DumpOptions) -> &'static str {
+    match options.format.as_str() {
+        "text" => "py",
+        "json" => "json",
+        "yaml" => "yml",
+        _ => "txt",
+    }
+}
+
+fn collect_bins(dir: &Path, out: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).expect("Failed to read directory!") {
+        let path = entry.expect("Failed to read directory!").path();
+        if path.is_dir() {
+            collect_bins(&path, out);
+        } else if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")) {
+            out.push(path);
+        }
+    }
+}
+
+fn entry_label(name: &BinFNV) -> String {
+    if name.get_string().is_empty() {
+        format!("0x{:08X}", name.get_hash())
+    } else {
+        name.get_string().to_string()
+    }
+}
+
+fn grep_paths(bin: &Bin, pattern: &str) -> Result<Vec<String>, String> {
+    if let Some(class) = pattern.strip_prefix("type:") {
+        Ok(bin.find_by_type(class))
+    } else if let Some(hash) = pattern.strip_prefix("hash:") {
+        let hash = hash.strip_prefix("0x").ok_or("Hash must start with 0x")?;
+        let hash = u64::from_str_radix(hash, 16).map_err(|e| e.to_string())?;
+        Ok(bin.find_hash(hash))
+    } else {
+        Ok(bin.find_string(pattern))
+    }
+}
+
+fn checksum_rows(path: &Path, hashes: &BinHashes, quiet: bool) -> Vec<Vec<String>> {
+    let (root, paths) = if path.is_dir() {
+        let mut paths = Vec::new();
+        collect_bins(path, &mut paths);
+        (path, paths)
+    } else {
+        (path.parent().unwrap_or(Path::new("")), vec![path.to_path_buf()])
+    };
+    let rows: Vec<Vec<Vec<String>>> = paths
+        .par_iter()
+        .map(|path| {
+            let name = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+            let bin = Bin::read_from_path(path, hashes);
+            match bin {
+                Ok(bin) => bin
+                    .entry_checksums()
+                    .into_iter()
+                    .map(|(entry, checksum)| vec![name.clone(), entry_label(&entry), format!("{:016x}", checksum)])
+                    .collect(),
+                Err(error) => {
+                    if !quiet {
+                        eprintln!("{}: {}", path.display(), error);
+                    }
+                    Vec::new()
+                }
+            }
+        })
+        .collect();
+    rows.concat()
+}
+
+fn graph_rows(path: &Path, hashes: &BinHashes, roots: &[BinFNV], quiet: bool) -> Vec<Vec<String>> {
+    let (root, paths) = if path.is_dir() {
+        let mut paths = Vec::new();
+        collect_bins(path, &mut paths);
+        (path, paths)
+    } else {
+        (path.parent().unwrap_or(Path::new("")), vec![path.to_path_buf()])
+    };
+    let bins: Vec<(String, Bin)> = paths
+        .par_iter()
+        .filter_map(|path| {
+            let name = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+            match Bin::read_from_path(path, hashes) {
+                Ok(bin) => Some((name, bin)),
+                Err(error) => {
+                    if !quiet {
+                        eprintln!("{}: {}", path.display(), error);
+                    }
+                    None
+                }
+            }
+        })
+        .collect();
+    let mut collection = BinCollection::new();
+    for (name, bin) in bins {
+        collection.insert(&name, bin);
+    }
+    let metrics = collection.graph_metrics(roots);
+    metrics
+        .entries
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.path.clone(),
+                entry_label(&entry.name),
+                entry.in_degree.to_string(),
+                entry.out_degree.to_string(),
+                entry.dangling.to_string(),
+                metrics.components[entry.component].len().to_string(),
+                entry.cluster.to_string(),
+                metrics.orphaned.contains(&entry.cluster).to_string(),
+            ]
+        })
+        .collect()
+}
+
+fn unresolved_rows(path: &Path, hashes: &BinHashes, quiet: bool) -> Vec<Vec<String>> {
+    let (root, paths) = if path.is_dir() {
+        let mut paths = Vec::new();
+        collect_bins(path, &mut paths);
+        (path, paths)
+    } else {
+        (path.parent().unwrap_or(Path::new("")), vec![path.to_path_buf()])
+    };
+    let results: Vec<(String, BinUnresolved)> = paths
+        .par_iter()
+        .filter_map(|path| {
+            let name = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+            let bin = Bin::read_from_path(path, hashes);
+            match bin {
+                Ok(bin) => Some((name, bin.unresolved_hashes())),
+                Err(error) => {
+                    if !quiet {
+                        eprintln!("{}: {}", path.display(), error);
+                    }
+                    None
+                }
+            }
+        })
+        .collect();
+    let mut total = BinUnresolved::default();
+    let mut rows = Vec::new();
+    for (name, unresolved) in &results {
+        total.merge(unresolved);
+        let mut row = vec![name.clone()];
+        row.extend(unresolved.summary().into_iter().map(|(_, count)| count.to_string()));
+        rows.push(row);
+    }
+    let mut row = vec!["total".to_string()];
+    row.extend(total.summary().into_iter().map(|(_, count)| count.to_string()));
+    rows.push(row);
+    rows
+}
+
+fn compare_checksums(rows: &[Vec<String>], old: &str) -> Vec<Vec<String>> {
+    let mut old: HashMap<(&str, &str), &str> = old
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some(((fields.next()?, fields.next()?), fields.next()?))
+        })
+        .collect();
+    let mut result = Vec::new();
+    for row in rows {
+        match old.remove(&(row[0].as_str(), row[1].as_str())) {
+            Some(checksum) if checksum == row[2] => {}
+            Some(_) => result.push(vec!["changed".to_string(), row[0].clone(), row[1].clone()]),
+            None => result.push(vec!["added".to_string(), row[0].clone(), row[1].clone()]),
+        }
+    }
+    let mut removed: Vec<(&str, &str)> = old.into_keys().collect();
+    removed.sort();
+    for (file, entry) in removed {
+        result.push(vec!["removed".to_string(), file.to_string(), entry.to_string()]);
+    }
+    result
+}
+
+fn dump_dir(dir: &Path, out_dir: &Path, hashes: &BinHashes, options: &DumpOptions) {
+    let mut paths = Vec::new();
+    collect_bins(dir, &mut paths);
+    let extension = output_extension(options);
+    let failed = paths
+        .par_iter()
+        .filter(|path| {
+            let result = fs::read(path).and_then(|data| {
+                let bin = Bin::read_from_data_with_options(&data, hashes, &read_options(options))?;
+                let out_path = out_dir.join(path.strip_prefix(dir).unwrap()).with_extension(extension);
+                fs::create_dir_all(out_path.parent().unwrap())?;
+                fs::write(out_path, dump_bin(bin, hashes, options))
+            });
+            if let Err(error) = &result {
+                if !options.quiet {
+                    eprintln!("{}: {}", path.display(), error);
+                }
+            }
+            result.is_err()
+        })
+        .count();
+    if !options.quiet {
+        eprintln!("Dumped {} of {} files", paths.len() - failed, paths.len());
+    }
+}
+
+fn sanitize_segment(segment: &str) -> String {
+    let segment: String = segment
+        .chars()
+        .map(|c| match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '.' | '_' | '-' | ' ' => c,
+            _ => '_',
+        })
+        .collect();
+    match segment.trim_matches(|c| c == '.' || c == ' ') {
+        "" => "_".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+fn entry_file_name(name: &BinFNV) -> PathBuf {
+    entry_label(name).split(['/', '\\']).map(sanitize_segment).collect()
+}
+
+fn export_entries(path: &Path, out_dir: &Path, hashes: &BinHashes, options: &DumpOptions) {
+    let (root, paths) = if path.is_dir() {
+        let mut paths = Vec::new();
+        collect_bins(path, &mut paths);
+        paths.sort();
+        (path, paths)
+    } else {
+        (path.parent().unwrap_or(Path::new("")), vec![path.to_path_buf()])
+    };
+    let read = |path: &PathBuf| match read_export_bin(path, hashes, options) {
+        Ok(bin) => Some(bin),
+        Err(error) => {
+            if !options.quiet {
+                eprintln!("{}: {}", path.display(), error);
+            }
+            None
+        }
+    };
+    let names: Vec<Option<Vec<BinFNV>>> = paths
+        .par_iter()
+        .map(|path| read(path).map(|bin| bin.entries.keys().copied().collect()))
+        .collect();
+    let mut written = HashSet::new();
+    let out_paths: Vec<Option<Vec<PathBuf>>> = names
+        .iter()
+        .map(|names| names.as_ref().map(|names| entry_out_paths(names, out_dir, &mut written)))
+        .collect();
+    let files = paths
+        .par_iter()
+        .zip(out_paths.par_iter())
+        .filter_map(|(path, out_paths)| Some((path, out_paths.as_ref()?)))
+        .filter(|(path, out_paths)| match read(path) {
+            Some(bin) => {
+                let file = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+                write_entries(&file, &bin, out_paths);
+                true
+            }
+            None => false,
+        })
+        .count();
+    if !options.quiet {
+        eprintln!("Exported {} entries from {} files", written.len(), files);
+    }
+}
+
+fn read_export_bin(path: &Path, hashes: &BinHashes, options: &DumpOptions) -> Result<Bin, BinError> {
+    let data = fs::read(path)?;
+    let mut bin = Bin::read_from_data_with_options(&data, hashes, &read_options(options))?;
+    prepare_bin(&mut bin, options);
+    Ok(bin)
+}
+
+fn entry_out_paths(names: &[BinFNV], out_dir: &Path, written: &mut HashSet<String>) -> Vec<PathBuf> {
+    names
+        .iter()
+        .map(|name| {
+            let base = out_dir.join(entry_file_name(name)).to_string_lossy().to_string();
+            let mut out_path = format!("{}.json", base);
+            let mut index = 1;
+            while !written.insert(out_path.to_ascii_lowercase()) {
+                index += 1;
+                out_path = format!("{}~{}.json", base, index);
+            }
+            PathBuf::from(out_path)
+        })
+        .collect()
+}
+
+struct EntryJson<'a> {
+    name: &'a BinFNV,
+    file: &'a str,
+    value: &'a BinValue,
+}
+
+impl Serialize for EntryJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(4))?;
+        map.serialize_entry("name", &entry_label(self.name))?;
+        map.serialize_entry("hash", &format!("0x{:08X}", self.name.get_hash()))?;
+        map.serialize_entry("file", self.file)?;
+        map.serialize_entry("value", &BinJson(self.value))?;
+        map.end()
+    }
+}
+
+fn write_entries(file: &str, bin: &Bin, out_paths: &[PathBuf]) {
+    for ((name, value), out_path) in bin.entries.iter().zip(out_paths) {
+        let json = EntryJson { name, file, value };
+        fs::create_dir_all(out_path.parent().unwrap()).expect("Failed to create output directory!");
+        fs::write(out_path, serde_json::to_string_pretty(&json).unwrap()).expect("Failed to write output!");
+    }
+}
+
+#[cfg(feature = "wad")]
+fn is_wad(path: &Path) -> bool {
+    let name = path.to_string_lossy().to_ascii_lowercase();
+    name.ends_with(".wad") || name.ends_with(".wad.client")
+}
+
+#[cfg(feature = "wad")]
+fn dump_wad(path: &Path, out_dir: &Path, hashes: &BinHashes, options: &DumpOptions) {
+    let wad = BinWad::read_from_path(path, hashes).expect("Failed to read wad!");
+    let extension = output_extension(options);
+    let bins = wad.read_bins(hashes);
+    let total = bins.len();
+    let mut failed = 0;
+    for (chunk, bin) in bins {
+        let name = match chunk.path.get_string() {
+            "" => format!("{:016x}.bin", chunk.path.get_hash()),
+            name => name.to_string(),
+        };
+        let result = bin.map_err(io::Error::from).and_then(|bin| {
+            let out_path = out_dir.join(&name).with_extension(extension);
+            fs::create_dir_all(out_path.parent().unwrap())?;
+            fs::write(out_path, dump_bin(bin, hashes, options))
+        });
+        if let Err(error) = result {
+            failed += 1;
+            if !options.quiet {
+                eprintln!("{}: {}", name, error);
+            }
+        }
+    }
+    if !options.quiet {
+        eprintln!("Dumped {} of {} bins", total - failed, total);
+        let skipped = wad.chunks.iter().filter(|chunk| !chunk.is_supported()).count();
+        if skipped != 0 {
+            eprintln!("Skipped {} chunks with unsupported (zstd) compression", skipped);
+        }
+    }
+}
+
+fn resolve_nested(bin: &Bin, source: &Path, hashes: &BinHashes) -> BinNested {
+    #[cfg(feature = "wad")]
+    if is_wad(source) {
+        let wad = BinWad::read_from_path(source, hashes).expect("Failed to read wad!");
+        return bin.resolve_nested(&wad, hashes, NESTED_DEPTH);
+    }
+    bin.resolve_nested(&source.to_path_buf(), hashes, NESTED_DEPTH)
+}
+
+fn guess_hashes(dir: &Path, hashes: &BinHashes, options: &DumpOptions) -> String {
+    let mut paths = Vec::new();
+    collect_bins(dir, &mut paths);
+    let mut guesser = BinHashGuesser::new();
+    for path in &paths {
+        let bin = Bin::read_from_path(path, hashes);
+        match bin {
+            Ok(bin) => guesser.add_bin(&bin),
+            Err(error) if !options.quiet => eprintln!("{}: {}", path.display(), error),
+            Err(_) => {}
+        }
+    }
+    let guesses = guesser.guess();
+    if !options.quiet {
+        eprintln!("Resolved {} of {} unknown hashes", guesses.len(), guesser.unknown_count());
+    }
+    let rows: Vec<Vec<String>> = guesses
+        .iter()
+        .map(|guess| {
+            let hash = match guess.kind {
+                BinGuessKind::Path => format!("{:016x}", guess.hash),
+                _ => format!("{:08x}", guess.hash),
+            };
+            vec![format!("{:?}", guess.kind).to_lowercase(), hash, guess.name.clone()]
+        })
+        .collect();
+    format_rows(&["kind", "hash", "name"], &rows, &options.format, " ")
+}
+
+#[cfg(not(feature = "bench"))]
+fn bench(_: &Path, _: &BinHashes, _: &DumpOptions, _: usize, _: bool) {
+    panic!("Bench mode needs rbindump built with the bench feature");
+}
 
 fn main() {
-    let path = env::args().nth(1).expect("No filename");
-    let file = fs::read(path).expect("Failed to read file!");
-    let mut hashes = BinHashes::new();
-    hashes.fields.read_from_file(File::open("hashes/hashes.binfields.txt").expect("Missing hashes.binfields.txt")).unwrap();
-    hashes.types.read_from_file(File::open("hashes/hashes.bintypes.txt").expect("Missing hashes.bintypes.txt")).unwrap();
-    hashes.hashes.read_from_file(File::open("hashes/hashes.binhashes.txt").expect("Missing hashes.binhashes.txt")).unwrap();
-    hashes.entries.read_from_file(File::open("hashes/hashes.binentries.txt").expect("Missing hashes.binentries.txt")).unwrap();
-    hashes.paths.read_from_file(File::open("hashes/hashes.game.txt").expect("Missing hashes.game.txt")).unwrap();
-    let bin = Bin::read_from_data(file.as_slice(), &hashes).expect("Failed to read bin!");
-    print!("{:#?}", bin);
+    let mut path = None;
+    let mut out_dir = None;
+    let mut options = DumpOptions {
+        compact: false,
+        format: "debug".to_string(),
+        curves: false,
+        only: Vec::new(),
+        exclude: Vec::new(),
+        quiet: false,
+        sorted: false,
+        guess_unknown: false,
+        renames: None,
+    };
+    let mut output = None;
+    let mut manifest = None;
+    let mut compare = None;
+    let mut checksums = false;
+    let mut hashes_dir = None;
+    let mut update_hashes = false;
+    let mut grep = None;
+    let mut nested = None;
+    let mut checksums_compare = None;
+    let mut bench_mode = false;
+    let mut guess_mode = false;
+    let mut repl_mode = false;
+    let mut unresolved = false;
+    let mut hash_display = BinHashDisplay::default();
+    let mut iterations = 5;
+    let mut render = false;
+    let mut sample = None;
+    let mut entries = Vec::new();
+    let mut graph = false;
+    let mut split_entries = false;
+    let mut roots = Vec::new();
+    let mut random_sample = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--compact" => options.compact = true,
+            "--format" => options.format = args.next().expect("Missing format"),
+            "--curves" => options.curves = true,
+            "-o" | "--output" => output = Some(PathBuf::from(args.next().expect("Missing output file"))),
+            "--quiet" => options.quiet = true,
+            "--sorted" => options.sorted = true,
+            "--guess-unknown" => options.guess_unknown = true,
+            "--sample" => sample = Some(args.next().expect("Missing sample size").parse().expect("Bad sample size")),
+            "--random-sample" => random_sample = true,
+            "--entry" => entries.push(args.next().expect("Missing entry name")),
+            "--rename" => {
+                let file = fs::File::open(args.next().expect("Missing rename map")).expect("Failed to open rename map!");
+                options.renames = Some(BinRenameMap::read_from_file(file).expect("Failed to read rename map!"));
+            }
+            "--class-name" => {
+                let arg = args.next().expect("Missing class name");
+                let (class, name) = arg.split_once('=').expect("Class name must be CLASS=NAME");
+                BinClassDisplay::register_hash(parse_name(class), BinClassDisplay::named(name));
+            }
+            "--inline-class" => BinClassDisplay::register_hash(
+                parse_name(&args.next().expect("Missing class name")),
+                BinClassDisplay::inline(),
+            ),
+            "--out" => out_dir = Some(PathBuf::from(args.next().expect("Missing output directory"))),
+            "--hashes-dir" => hashes_dir = Some(args.next().expect("Missing hashes directory")),
+            "--update-hashes" => update_hashes = true,
+            "--grep" => grep = Some(args.next().expect("Missing pattern")),
+            "--nested" => nested = Some(PathBuf::from(args.next().expect("Missing nested source"))),
+            "--checksums" => checksums = true,
+            "--unresolved" => unresolved = true,
+            "--graph" => graph = true,
+            "--split-entries" => split_entries = true,
+            "--root" => roots.push(BinFNV::from_hash(parse_name(&args.next().expect("Missing root entry")))),
+            "--hash-style" => {
+                hash_display.style = match args.next().expect("Missing hash style").as_str() {
+                    "hex" => BinHashStyle::Hex,
+                    "decimal" => BinHashStyle::Decimal,
+                    "wrapped" => BinHashStyle::Wrapped,
+                    style => panic!("Unknown hash style {}", style),
+                }
+            }
+            "--show-hashes" => hash_display.show_hash = true,
+            "--checksums-compare" => checksums_compare = Some(args.next().expect("Missing checksum manifest")),
+            "--compare" => compare = Some(args.next().expect("Missing reference file")),
+            "--manifest" => manifest = Some(parse_name(&args.next().expect("Missing entry name"))),
+            "--only-fields" => options.only = parse_field_names(&args.next().expect("Missing field list")),
+            "bench" if path.is_none() && !bench_mode && !guess_mode && !repl_mode => bench_mode = true,
+            "guess" if path.is_none() && !bench_mode && !guess_mode && !repl_mode => guess_mode = true,
+            "repl" if path.is_none() && !bench_mode && !guess_mode && !repl_mode => repl_mode = true,
+            "--iterations" => iterations = args.next().expect("Missing iteration count").parse().expect("Bad iteration count"),
+            "--render" => render = true,
+            "--exclude-fields" => options.exclude = parse_field_names(&args.next().expect("Missing field list")),
+            _ if arg.starts_with('-') && arg != "-" => panic!("Unknown argument {}", arg),
+            _ if path.is_some() => panic!("Unexpected argument {}, only one input can be given", arg),
+            _ => path = Some(arg),
+        }
+    }
+    if options.compact && options.format != "debug" {
+        panic!("--compact only applies to --format debug");
+    }
+    hash_display.set();
+    let hashes_dir = hashes_dir
+        .or_else(|| env::var("RBIN_HASHES_DIR").ok())
+        .unwrap_or_else(|| "hashes".to_string());
+    if update_hashes {
+        update_hashes_dir(&hashes_dir);
+    }
+    let hashes = BinHashes::load_from_dir(hashes_dir).expect("Failed to read hashes!");
+    if repl_mode {
+        repl::run(&hashes, path.map(PathBuf::from));
+        return;
+    }
+    let path = PathBuf::from(path.expect("No filename"));
+    if bench_mode {
+        bench(&path, &hashes, &options, iterations, render);
+        return;
+    }
+    if guess_mode {
+        emit(output.as_deref(), &guess_hashes(&path, &hashes, &options));
+        return;
+    }
+    if graph {
+        let rows = graph_rows(&path, &hashes, &roots, options.quiet);
+        let headers = ["file", "entry", "in", "out", "dangling", "scc", "cluster", "orphaned"];
+        emit(output.as_deref(), &format_rows(&headers, &rows, &options.format, "\t"));
+        return;
+    }
+    if unresolved {
+        let rows = unresolved_rows(&path, &hashes, options.quiet);
+        let headers = ["file", "entry", "field", "type", "hash", "path"];
+        emit(output.as_deref(), &format_rows(&headers, &rows, &options.format, "\t"));
+        return;
+    }
+    if checksums || checksums_compare.is_some() {
+        let rows = checksum_rows(&path, &hashes, options.quiet);
+        match checksums_compare {
+            Some(old) => {
+                let old = fs::read_to_string(old).expect("Failed to read checksum manifest!");
+                let rows = compare_checksums(&rows, &old);
+                emit(output.as_deref(), &format_rows(&["status", "file", "entry"], &rows, &options.format, "\t"));
+            }
+            None => emit(output.as_deref(), &format_rows(&["file", "entry", "checksum"], &rows, &options.format, "\t")),
+        }
+        return;
+    }
+    if split_entries {
+        let out_dir = out_dir.expect("Splitting entries needs --out");
+        export_entries(&path, &out_dir, &hashes, &options);
+        return;
+    }
+    #[cfg(feature = "wad")]
+    if is_wad(&path) {
+        let out_dir = out_dir.expect("Dumping a wad needs --out");
+        dump_wad(&path, &out_dir, &hashes, &options);
+        return;
+    }
+    if path.is_dir() {
+        let out_dir = out_dir.expect("Dumping a directory needs --out");
+        dump_dir(&path, &out_dir, &hashes, &options);
+        return;
+    }
+    let file = if path == Path::new("-") {
+        let mut data = Vec::new();
+        io::stdin().lock().read_to_end(&mut data).expect("Failed to read stdin!");
+        data
+    } else {
+        fs::read(&path).expect("Failed to read file!")
+    };
+    if !entries.is_empty() {
+        let bin = extract_entries(&file, &hashes, &options, &entries);
+        emit(output.as_deref(), &dump_bin(bin, &hashes, &options));
+        return;
+    }
+    if let Some(sample) = sample {
+        let bin = sample_bin(&file, &hashes, &options, sample, random_sample);
+        emit(output.as_deref(), &dump_bin(bin, &hashes, &options));
+        return;
+    }
+    let bin = Bin::read_from_data_with_options(file.as_slice(), &hashes, &read_options(&options))
+        .expect("Failed to read bin!");
+    if let Some(reference) = compare {
+        let reference = fs::read_to_string(reference).expect("Failed to read reference!");
+        let reference = serde_json::from_str(&reference).expect("Bad reference json!");
+        let rows: Vec<Vec<String>> = compare_json(&bin.to_json(), &reference)
+            .into_iter()
+            .map(|(path, message)| vec![path, message])
+            .collect();
+        emit(output.as_deref(), &format_rows(&["path", "message"], &rows, &options.format, ": "));
+        return;
+    }
+    if let Some(pattern) = grep {
+        let paths = grep_paths(&bin, &pattern).expect("Bad hash");
+        let rows: Vec<Vec<String>> = paths.into_iter().map(|path| vec![path]).collect();
+        emit(output.as_deref(), &format_rows(&["path"], &rows, &options.format, "\t"));
+        return;
+    }
+    if let Some(root) = manifest {
+        let manifest = bin.asset_manifest(&BinFNV::from_hash(root));
+        let mut rows = Vec::new();
+        for entry in &manifest.entries {
+            rows.push(vec!["entry".to_string(), entry_label(entry)]);
+        }
+        for file in &manifest.files {
+            rows.push(vec!["file".to_string(), file.clone()]);
+        }
+        emit(output.as_deref(), &format_rows(&["kind", "name"], &rows, &options.format, "\t"));
+        return;
+    }
+    let nested = nested.map(|source| resolve_nested(&bin, &source, &hashes));
+    let mut text = dump_bin(bin, &hashes, &options);
+    if let Some(nested) = nested {
+        for (path, bin) in nested.bins {
+            write!(text, "\n# {}\n{}", path, dump_bin(bin, &hashes, &options)).unwrap();
+        }
+        if !options.quiet {
+            for path in &nested.missing {
+                eprintln!("{}: not found", path);
+            }
+        }
+    }
+    match out_dir {
+        Some(out_dir) => {
+            let name = path.file_name().expect("No filename");
+            fs::create_dir_all(&out_dir).expect("Failed to create output directory!");
+            fs::write(out_dir.join(name).with_extension(output_extension(&options)), text).expect("Failed to write output!");
+        }
+        None => emit(output.as_deref(), &text),
+    }
 }
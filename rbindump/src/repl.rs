@@ -0,0 +1,377 @@
+use crate::{entry_label, grep_paths};
+use rbin::*;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+const HELP: &str = "\
+open <file>            load a bin file
+ls                     list entries or fields at the current location
+cd <path>|..|/         move between entry directories, entries and values
+pwd                    print the current location
+cat [query]            print the value at the current location or query
+search <pattern>       find paths by string, type:<class> or hash:0x<hash>
+set <query> <value>    replace a value, keeping its type
+complete <prefix>      list names starting with prefix from the bin and hash lists
+write [file]           save the bin, defaulting to the opened file
+quit                   leave the shell";
+
+fn split_steps(query: &str) -> Option<Vec<String>> {
+    let mut steps = Vec::new();
+    let mut rest = query;
+    while !rest.is_empty() {
+        if rest.starts_with('[') {
+            let end = rest.find(']')? + 1;
+            steps.push(rest[..end].to_string());
+            rest = &rest[end..];
+        } else {
+            let field = rest.strip_prefix('.').unwrap_or(rest);
+            let end = field.find(['.', '[']).unwrap_or(field.len());
+            if end == 0 {
+                return None;
+            }
+            steps.push(format!(".{}", &field[..end]));
+            rest = &field[end..];
+        }
+    }
+    Some(steps)
+}
+
+fn key_label(key: &BinValue) -> String {
+    match key {
+        BinValue::String(value) => value.clone(),
+        BinValue::Hash(name) | BinValue::Link(name) => entry_label(name),
+        BinValue::File(file) if !file.get_string().is_empty() => file.get_string().to_string(),
+        BinValue::File(file) => format!("0x{:016X}", file.get_hash()),
+        _ => key.to_string(),
+    }
+}
+
+fn describe(value: &BinValue) -> String {
+    let kind = value.bin_type().name();
+    match value {
+        BinValue::Struct(name, _) | BinValue::Embed(name, _) => format!("{} {}", kind, entry_label(name)),
+        BinValue::List(_, items) | BinValue::List2(_, items) => format!("{}[{}]", kind, items.len()),
+        BinValue::Option(_, value) => format!("{}[{}]", kind, value.is_some() as usize),
+        BinValue::Map(_, _, items) => format!("{}[{}]", kind, items.len()),
+        BinValue::Unknown(_, bytes, _) => format!("{}[{}]", kind, bytes.len()),
+        _ => format!("{} = {}", kind, value),
+    }
+}
+
+fn parse_literal(target: &BinValue, text: &str) -> BinValue {
+    let quoted = text.strip_prefix('"').and_then(|text| text.strip_suffix('"'));
+    let string = quoted.unwrap_or(text);
+    match target {
+        BinValue::String(_) => BinValue::String(string.to_string()),
+        BinValue::Hash(_) => BinValue::hash_name(string),
+        BinValue::Link(_) => BinValue::link_name(string),
+        BinValue::File(_) => BinValue::file_path(string),
+        _ if quoted.is_some() => BinValue::String(string.to_string()),
+        _ => {
+            if let Ok(value) = text.parse::<bool>() {
+                BinValue::Bool(value)
+            } else if let Ok(value) = text.parse::<i64>() {
+                BinValue::I64(value)
+            } else if let Ok(value) = text.parse::<u64>() {
+                BinValue::U64(value)
+            } else if let Ok(value) = text.parse::<f32>() {
+                BinValue::Float(value)
+            } else {
+                BinValue::String(text.to_string())
+            }
+        }
+    }
+}
+
+struct Session<'a> {
+    hashes: &'a BinHashes,
+    file: Option<(PathBuf, Bin)>,
+    dir: Vec<String>,
+    entry: Option<BinFNV>,
+    steps: Vec<String>,
+}
+
+impl<'a> Session<'a> {
+    fn location(&self) -> String {
+        match &self.entry {
+            Some(entry) => format!("{}{}", entry_label(entry), self.steps.concat()),
+            None => join_entry_path(&self.dir),
+        }
+    }
+
+    fn bin(&self) -> Result<&Bin, String> {
+        self.file.as_ref().map(|(_, bin)| bin).ok_or_else(|| "No file open".to_string())
+    }
+
+    fn open(&mut self, path: &str) -> Result<(), String> {
+        let bin = Bin::read_from_path(path, self.hashes).map_err(|e| e.to_string())?;
+        println!("{} entries", bin.entries.len());
+        self.file = Some((PathBuf::from(path), bin));
+        self.dir.clear();
+        self.entry = None;
+        self.steps.clear();
+        Ok(())
+    }
+
+    fn find_entry(&self, name: &str) -> Result<Option<BinFNV>, String> {
+        let key = match name.strip_prefix("0x").and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+            Some(hash) => BinFNV::from_hash(hash),
+            None => {
+                let mut segments = self.dir.clone();
+                segments.extend(split_entry_path(name).into_iter().map(str::to_string));
+                BinFNV::from_string(&join_entry_path(&segments))
+            }
+        };
+        Ok(self.bin()?.entries.get_key_value(&key).map(|(name, _)| *name))
+    }
+
+    fn resolve(&self, query: &str) -> Result<(BinFNV, String), String> {
+        if let Some(entry) = self.entry {
+            let steps = split_steps(query).ok_or_else(|| format!("Bad query {:?}", query))?;
+            return Ok((entry, format!("{}{}", self.steps.concat(), steps.concat())));
+        }
+        let end = query.find(['.', '[']).unwrap_or(query.len());
+        let (name, rest) = query.split_at(end);
+        match self.find_entry(name)? {
+            Some(entry) => Ok((entry, rest.to_string())),
+            None => Err(format!("No entry {:?}", name)),
+        }
+    }
+
+    fn value(&self, query: &str) -> Result<&BinValue, String> {
+        let (entry, rest) = self.resolve(query)?;
+        self.bin()?.entries[&entry]
+            .select(&rest)
+            .ok_or_else(|| format!("Nothing at {:?}", query))
+    }
+
+    fn children(&self) -> Result<Vec<(String, String)>, String> {
+        if self.entry.is_some() {
+            let result = match self.value("")? {
+                BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+                    fields.iter().map(|(name, value)| (entry_label(name), describe(value))).collect()
+                }
+                BinValue::List(_, items) | BinValue::List2(_, items) => items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| (format!("[{}]", index), describe(value)))
+                    .collect(),
+                BinValue::Option(_, value) => value
+                    .iter()
+                    .map(|value| ("[0]".to_string(), describe(value)))
+                    .collect(),
+                BinValue::Map(_, _, items) => items
+                    .iter()
+                    .map(|(key, value)| (format!("[{}]", key_label(key)), describe(value)))
+                    .collect(),
+                value => vec![(String::new(), describe(value))],
+            };
+            return Ok(result);
+        }
+        let prefix = join_entry_path(&self.dir);
+        let mut dirs = BTreeSet::new();
+        let mut result = Vec::new();
+        for (name, value) in self.bin()?.entries_with_prefix(&prefix) {
+            let segments = split_entry_path(name.get_string());
+            match segments.len() {
+                0 if self.dir.is_empty() => result.push((entry_label(name), describe(value))),
+                len if len == self.dir.len() + 1 => result.push((segments[len - 1].to_string(), describe(value))),
+                len if len > self.dir.len() + 1 => {
+                    dirs.insert(format!("{}/", segments[self.dir.len()]));
+                }
+                _ => {}
+            }
+        }
+        result.extend(dirs.into_iter().map(|dir| (dir, String::new())));
+        Ok(result)
+    }
+
+    fn ls(&self) -> Result<(), String> {
+        let mut children = self.children()?;
+        children.sort();
+        for (name, description) in children {
+            match (name.is_empty(), description.is_empty()) {
+                (true, _) => println!("{}", description),
+                (_, true) => println!("{}", name),
+                _ => println!("{}\t{}", name, description),
+            }
+        }
+        Ok(())
+    }
+
+    fn cd(&mut self, arg: &str) -> Result<(), String> {
+        match arg {
+            "/" | "" => {
+                self.dir.clear();
+                self.entry = None;
+                self.steps.clear();
+            }
+            _ if arg.starts_with('/') => {
+                self.cd("/")?;
+                return self.cd(&arg[1..]);
+            }
+            ".." if self.entry.is_some() => {
+                if self.steps.pop().is_none() {
+                    self.entry = None;
+                }
+            }
+            ".." => {
+                self.dir.pop();
+            }
+            _ if self.entry.is_some() => {
+                let steps = split_steps(arg).ok_or_else(|| format!("Bad query {:?}", arg))?;
+                self.value(&steps.concat())?;
+                self.steps.extend(steps);
+            }
+            _ => {
+                let (entry, rest) = match self.resolve(arg) {
+                    Ok(found) => found,
+                    Err(error) => {
+                        let mut segments = self.dir.clone();
+                        segments.extend(split_entry_path(arg).into_iter().map(str::to_string));
+                        if self.bin()?.entries_with_prefix(&join_entry_path(&segments)).is_empty() {
+                            return Err(error);
+                        }
+                        self.dir = segments;
+                        return Ok(());
+                    }
+                };
+                let steps = split_steps(&rest).ok_or_else(|| format!("Bad query {:?}", arg))?;
+                self.value(arg)?;
+                let segments = split_entry_path(entry.get_string());
+                self.dir = segments[..segments.len().saturating_sub(1)]
+                    .iter()
+                    .map(|segment| segment.to_string())
+                    .collect();
+                self.entry = Some(entry);
+                self.steps = steps;
+            }
+        }
+        Ok(())
+    }
+
+    fn search(&self, pattern: &str) -> Result<(), String> {
+        let location = self.location();
+        for path in grep_paths(self.bin()?, pattern)? {
+            let inside = match self.entry {
+                Some(_) => path.starts_with(&location),
+                None => entry_path_has_prefix(&path, &location),
+            };
+            if inside {
+                println!("{}", path);
+            }
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, query: &str, text: &str) -> Result<(), String> {
+        let (entry, rest) = self.resolve(query)?;
+        let bin = self.file.as_mut().map(|(_, bin)| bin).ok_or("No file open")?;
+        let target = bin
+            .entries
+            .get_mut(&entry)
+            .and_then(|value| value.select_mut(&rest))
+            .ok_or_else(|| format!("Nothing at {:?}", query))?;
+        let value = parse_literal(target, text);
+        target.set_in_place(value)?;
+        println!("{}", describe(target));
+        Ok(())
+    }
+
+    fn complete(&self, prefix: &str) -> Result<(), String> {
+        let lower = prefix.to_ascii_lowercase();
+        let mut names: BTreeSet<String> = self
+            .children()?
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| name.to_ascii_lowercase().starts_with(&lower))
+            .collect();
+        if self.entry.is_some() {
+            for name in self.hashes.fields.list.values() {
+                if name.get_string().to_ascii_lowercase().starts_with(&lower) {
+                    names.insert(name.get_string().to_string());
+                }
+            }
+        } else {
+            for name in self.hashes.entries.list.values() {
+                let segments = split_entry_path(name.get_string());
+                if entry_path_has_prefix(name.get_string(), &join_entry_path(&self.dir)) {
+                    if let Some(segment) = segments.get(self.dir.len()) {
+                        if segment.to_ascii_lowercase().starts_with(&lower) {
+                            names.insert(segment.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        for name in names {
+            println!("{}", name);
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: Option<&str>) -> Result<(), String> {
+        let (opened, bin) = self.file.as_ref().ok_or("No file open")?;
+        let path = path.map(PathBuf::from).unwrap_or_else(|| opened.clone());
+        let data = bin.write_to_data().map_err(|e| e.to_string())?;
+        fs::write(&path, data).map_err(|e| format!("{}: {}", path.display(), e))?;
+        println!("wrote {}", path.display());
+        Ok(())
+    }
+
+    fn execute(&mut self, line: &str) -> Result<bool, String> {
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        match command {
+            "" => {}
+            "help" => println!("{}", HELP),
+            "quit" | "exit" => return Ok(false),
+            "open" if !rest.is_empty() => self.open(rest)?,
+            "ls" => self.ls()?,
+            "cd" => self.cd(rest)?,
+            "pwd" => println!("/{}", self.location()),
+            "cat" => println!("{:#?}", self.value(rest)?),
+            "search" if !rest.is_empty() => self.search(rest)?,
+            "set" => match rest.split_once(char::is_whitespace) {
+                Some((query, text)) => self.set(query, text.trim())?,
+                None => return Err("Usage: set <query> <value>".to_string()),
+            },
+            "complete" => self.complete(rest)?,
+            "write" => self.write(Some(rest).filter(|rest| !rest.is_empty()))?,
+            _ => return Err(format!("Unknown command {:?}, try help", line)),
+        }
+        Ok(true)
+    }
+}
+
+pub fn run(hashes: &BinHashes, path: Option<PathBuf>) {
+    let mut session = Session {
+        hashes,
+        file: None,
+        dir: Vec::new(),
+        entry: None,
+        steps: Vec::new(),
+    };
+    if let Some(path) = path {
+        if let Err(error) = session.open(&path.to_string_lossy()) {
+            eprintln!("error: {}", error);
+        }
+    }
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("/{}> ", session.location());
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(line) => line.expect("Failed to read stdin!"),
+            None => break,
+        };
+        match session.execute(line.trim()) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(error) => eprintln!("error: {}", error),
+        }
+    }
+}
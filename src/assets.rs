@@ -0,0 +1,69 @@
+use crate::*;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug)]
+pub struct BinFileInfo {
+    pub path: PathBuf,
+    pub size: u64,
+    pub extension: Option<&'static str>,
+}
+
+pub struct BinAssetDir {
+    pub root: PathBuf,
+}
+
+fn sniff_extension(magic: &[u8]) -> Option<&'static str> {
+    const MAGICS: &[(&[u8], &str)] = &[
+        (b"DDS ", "dds"),
+        (b"TEX\0", "tex"),
+        (b"\x89PNG", "png"),
+        (b"PROP", "bin"),
+        (b"PTCH", "bin"),
+        (b"r3d2Mesh", "scb"),
+        (b"r3d2sklt", "skl"),
+        (b"r3d2anmd", "anm"),
+        (b"r3d2canm", "anm"),
+        (b"\x33\x22\x11\x00", "skn"),
+        (b"OggS", "ogg"),
+        (b"BKHD", "bnk"),
+        (b"RW", "wad"),
+    ];
+    MAGICS
+        .iter()
+        .find(|(prefix, _)| magic.starts_with(prefix))
+        .map(|(_, extension)| *extension)
+}
+
+impl BinAssetDir {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn resolve(&self, file: &BinXXH) -> Option<BinFileInfo> {
+        if file.get_string().is_empty() {
+            return None;
+        }
+        let path = self.root.join(file.get_string().to_ascii_lowercase());
+        let size = fs::metadata(&path).ok()?.len();
+        let mut magic = [0u8; 8];
+        let read = fs::File::open(&path).ok()?.read(&mut magic).ok()?;
+        Some(BinFileInfo {
+            path,
+            size,
+            extension: sniff_extension(&magic[..read]),
+        })
+    }
+}
+
+impl BinValue {
+    pub fn resolve_file(&self, assets: &BinAssetDir) -> Option<BinFileInfo> {
+        match self {
+            BinValue::File(file) => assets.resolve(file),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::*;
+
+pub(crate) fn hashed_name(name: &str) -> BinFNV {
+    match name.strip_prefix("0x").and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+        Some(hash) => BinFNV::from_hash(hash),
+        None => BinFNV::from_hash_string(fnv(name), name),
+    }
+}
+
+impl BinValue {
+    pub fn hash_name(name: &str) -> Self {
+        BinValue::Hash(hashed_name(name))
+    }
+
+    pub fn link_name(name: &str) -> Self {
+        BinValue::Link(hashed_name(name))
+    }
+
+    pub fn file_path(path: &str) -> Self {
+        BinValue::File(BinXXH::from_hash_string(xxh64(path), path))
+    }
+}
+
+pub struct BinStructBuilder {
+    type_name: BinFNV,
+    fields: IndexMap<BinFNV, BinValue>,
+}
+
+impl BinStructBuilder {
+    pub fn new(type_name: &str) -> Self {
+        Self {
+            type_name: hashed_name(type_name),
+            fields: IndexMap::new(),
+        }
+    }
+
+    pub fn field<V: Into<BinValue>>(mut self, name: &str, value: V) -> Self {
+        self.fields.insert(hashed_name(name), value.into());
+        self
+    }
+
+    pub fn pointer(self) -> BinValue {
+        BinValue::Struct(self.type_name, self.fields)
+    }
+
+    pub fn embed(self) -> BinValue {
+        BinValue::Embed(self.type_name, self.fields)
+    }
+}
+
+pub struct BinBuilder {
+    bin: Bin,
+}
+
+impl BinBuilder {
+    pub fn new() -> Self {
+        Self {
+            bin: Bin {
+                is_patch: false,
+                version: 3,
+                links: Vec::new(),
+                entries: IndexMap::new(),
+                patches: Vec::new(),
+                tail: Vec::new(),
+                errors: Vec::new(),
+            },
+        }
+    }
+
+    pub fn patch(mut self) -> Self {
+        self.bin.is_patch = true;
+        self
+    }
+
+    pub fn version(mut self, version: u32) -> Self {
+        self.bin.version = version;
+        self
+    }
+
+    pub fn link(mut self, link: &str) -> Self {
+        self.bin.links.push(link.to_string());
+        self
+    }
+
+    pub fn patch_value<V: Into<BinValue>>(mut self, entry: &str, path: &str, value: V) -> Self {
+        self.bin.patches.push(BinPatch {
+            name: hashed_name(entry),
+            path: path.to_string(),
+            value: value.into(),
+        });
+        self
+    }
+
+    pub fn entry(self, name: &str, type_name: &str) -> BinEntryBuilder {
+        BinEntryBuilder {
+            builder: self,
+            name: hashed_name(name),
+            value: BinStructBuilder::new(type_name),
+        }
+    }
+
+    pub fn build(self) -> Bin {
+        self.bin
+    }
+}
+
+impl Default for BinBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct BinEntryBuilder {
+    builder: BinBuilder,
+    name: BinFNV,
+    value: BinStructBuilder,
+}
+
+impl BinEntryBuilder {
+    pub fn field<V: Into<BinValue>>(mut self, name: &str, value: V) -> Self {
+        self.value = self.value.field(name, value);
+        self
+    }
+
+    pub fn done(self) -> BinBuilder {
+        let mut builder = self.builder;
+        builder.bin.entries.insert(self.name, self.value.embed());
+        builder
+    }
+
+    pub fn entry(self, name: &str, type_name: &str) -> BinEntryBuilder {
+        self.done().entry(name, type_name)
+    }
+
+    pub fn build(self) -> Bin {
+        self.done().build()
+    }
+}
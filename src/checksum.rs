@@ -0,0 +1,240 @@
+use crate::*;
+
+const CHECKSUM_BASIS: u64 = 0xcbf29ce484222325;
+const CHECKSUM_PRIME: u64 = 0x00000100000001b3;
+
+struct Checksum {
+    hash: u64,
+}
+
+impl Checksum {
+    fn write(&mut self, bytes: &[u8]) {
+        for c in bytes {
+            self.hash ^= *c as u64;
+            self.hash = self.hash.wrapping_mul(CHECKSUM_PRIME);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_floats(&mut self, values: &[f32]) {
+        for value in values {
+            self.write_u32(value.to_bits());
+        }
+    }
+
+    fn write_fields(&mut self, fields: &IndexMap<BinFNV, BinValue>) {
+        let mut fields: Vec<(&BinFNV, &BinValue)> = fields.iter().collect();
+        fields.sort_by_key(|(name, _)| name.get_hash());
+        self.write_u32(fields.len() as u32);
+        for (name, value) in fields {
+            self.write_u32(name.get_hash());
+            self.write_value(value);
+        }
+    }
+
+    fn write_value(&mut self, value: &BinValue) {
+        match value {
+            BinValue::None => self.write_u8(0),
+            BinValue::Bool(value) => self.write(&[1, *value as u8]),
+            BinValue::I8(value) => self.write(&[2, *value as u8]),
+            BinValue::U8(value) => self.write(&[3, *value]),
+            BinValue::I16(value) => {
+                self.write_u8(4);
+                self.write(&value.to_le_bytes());
+            }
+            BinValue::U16(value) => {
+                self.write_u8(5);
+                self.write(&value.to_le_bytes());
+            }
+            BinValue::I32(value) => {
+                self.write_u8(6);
+                self.write(&value.to_le_bytes());
+            }
+            BinValue::U32(value) => {
+                self.write_u8(7);
+                self.write_u32(*value);
+            }
+            BinValue::I64(value) => {
+                self.write_u8(8);
+                self.write(&value.to_le_bytes());
+            }
+            BinValue::U64(value) => {
+                self.write_u8(9);
+                self.write_u64(*value);
+            }
+            BinValue::Float(value) => {
+                self.write_u8(10);
+                self.write_floats(&[*value]);
+            }
+            BinValue::Vec2(value) => {
+                self.write_u8(11);
+                self.write_floats(value);
+            }
+            BinValue::Vec3(value) => {
+                self.write_u8(12);
+                self.write_floats(value);
+            }
+            BinValue::Vec4(value) => {
+                self.write_u8(13);
+                self.write_floats(value);
+            }
+            BinValue::Mtx44(value) => {
+                self.write_u8(14);
+                for row in value {
+                    self.write_floats(row);
+                }
+            }
+            BinValue::Rgba(value) => {
+                self.write_u8(15);
+                self.write(value);
+            }
+            BinValue::String(value) => {
+                self.write_u8(16);
+                self.write_u32(value.len() as u32);
+                self.write(value.as_bytes());
+            }
+            BinValue::Hash(value) => {
+                self.write_u8(17);
+                self.write_u32(value.get_hash());
+            }
+            BinValue::File(value) => {
+                self.write_u8(18);
+                self.write_u64(value.get_hash());
+            }
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                self.write_u8(if matches!(value, BinValue::List(_, _)) { 0x80 } else { 0x81 });
+                self.write_u32(items.len() as u32);
+                for item in items {
+                    self.write_value(item);
+                }
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                self.write_u8(if matches!(value, BinValue::Struct(_, _)) { 0x82 } else { 0x83 });
+                self.write_u32(name.get_hash());
+                self.write_fields(fields);
+            }
+            BinValue::Link(value) => {
+                self.write_u8(0x84);
+                self.write_u32(value.get_hash());
+            }
+            BinValue::Option(value_type, value) => {
+                self.write(&[0x85, *value_type as u8, value.is_some() as u8]);
+                if let Some(value) = value {
+                    self.write_value(value);
+                }
+            }
+            BinValue::Map(_, _, items) => {
+                self.write_u8(0x86);
+                self.write_u32(items.len() as u32);
+                for (key, value) in items {
+                    self.write_value(key);
+                    self.write_value(value);
+                }
+            }
+            BinValue::Flag(value) => self.write(&[0x87, *value as u8]),
+            BinValue::Unknown(type_id, bytes, _) => {
+                self.write(&[0xFF, *type_id]);
+                self.write_u32(bytes.len() as u32);
+                self.write(bytes);
+            }
+        }
+    }
+}
+
+fn shape_set<'a, I: Iterator<Item = &'a BinValue>>(items: I) -> Vec<u64> {
+    let mut shapes: Vec<u64> = items.map(BinValue::signature).collect();
+    shapes.sort_unstable();
+    shapes.dedup();
+    shapes
+}
+
+impl Checksum {
+    fn write_shapes(&mut self, shapes: &[u64]) {
+        self.write_u32(shapes.len() as u32);
+        for shape in shapes {
+            self.write_u64(*shape);
+        }
+    }
+
+    fn write_shape(&mut self, value: &BinValue) {
+        match value {
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                self.write_u8(if matches!(value, BinValue::List(_, _)) { 0x80 } else { 0x81 });
+                self.write_shapes(&shape_set(items.iter()));
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                self.write_u8(if matches!(value, BinValue::Struct(_, _)) { 0x82 } else { 0x83 });
+                self.write_u32(name.get_hash());
+                let mut fields: Vec<(&BinFNV, &BinValue)> = fields.iter().collect();
+                fields.sort_by_key(|(name, _)| name.get_hash());
+                self.write_u32(fields.len() as u32);
+                for (name, value) in fields {
+                    self.write_u32(name.get_hash());
+                    self.write_shape(value);
+                }
+            }
+            BinValue::Option(value_type, value) => {
+                self.write(&[0x85, *value_type as u8]);
+                self.write_shapes(&shape_set(value.as_deref().into_iter()));
+            }
+            BinValue::Map(_, _, items) => {
+                self.write_u8(0x86);
+                self.write_shapes(&shape_set(items.iter().map(|(key, _)| key)));
+                self.write_shapes(&shape_set(items.iter().map(|(_, value)| value)));
+            }
+            BinValue::Unknown(type_id, _, _) => self.write(&[0xFF, *type_id]),
+            _ => self.write_u8(value.bin_type() as u8),
+        }
+    }
+}
+
+impl BinValue {
+    pub fn signature(&self) -> u64 {
+        let mut checksum = Checksum {
+            hash: CHECKSUM_BASIS,
+        };
+        checksum.write_shape(self);
+        checksum.hash
+    }
+
+    pub fn checksum(&self) -> u64 {
+        let mut checksum = Checksum {
+            hash: CHECKSUM_BASIS,
+        };
+        checksum.write_value(self);
+        checksum.hash
+    }
+}
+
+impl Bin {
+    pub fn entry_checksums(&self) -> Vec<(BinFNV, u64)> {
+        let mut result: Vec<(BinFNV, u64)> = self
+            .entries
+            .iter()
+            .map(|(name, value)| (*name, value.checksum()))
+            .collect();
+        result.sort_by_key(|(name, _)| name.get_hash());
+        result
+    }
+
+    pub fn entry_signatures(&self) -> Vec<(BinFNV, u64)> {
+        let mut result: Vec<(BinFNV, u64)> = self
+            .entries
+            .iter()
+            .map(|(name, value)| (*name, value.signature()))
+            .collect();
+        result.sort_by_key(|(name, _)| name.get_hash());
+        result
+    }
+}
@@ -0,0 +1,106 @@
+use crate::*;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").to_ascii_lowercase()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BinCollection {
+    pub bins: HashMap<String, Bin>,
+    index: HashMap<BinFNV, String>,
+}
+
+impl BinCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, path: &str, bin: Bin) {
+        let path = normalize_path(path);
+        for name in bin.entries.keys() {
+            self.index.entry(*name).or_insert_with(|| path.clone());
+        }
+        self.bins.insert(path, bin);
+    }
+
+    fn load_dir(&mut self, root: &Path, dir: &Path, hashes: &BinHashes) -> Result<(), BinError> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                self.load_dir(root, &path, hashes)?;
+            } else if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")) {
+                let bin = Bin::read_from_path(&path, hashes)?;
+                let name = path.strip_prefix(root).unwrap().to_string_lossy().to_string();
+                self.insert(&name, bin);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load_from_dir<P: AsRef<Path>>(dir: P, hashes: &BinHashes) -> Result<Self, BinError> {
+        let mut result = Self::new();
+        result.load_dir(dir.as_ref(), dir.as_ref(), hashes)?;
+        Ok(result)
+    }
+
+    pub fn apply_renames(&mut self, map: &BinRenameMap) -> usize {
+        let mut count = 0;
+        self.index.clear();
+        for (path, bin) in &mut self.bins {
+            count += bin.apply_renames(map);
+            for name in bin.entries.keys() {
+                self.index.entry(*name).or_insert_with(|| path.clone());
+            }
+        }
+        count
+    }
+
+    pub fn get(&self, path: &str) -> Option<&Bin> {
+        self.bins.get(&normalize_path(path))
+    }
+
+    pub fn linked(&self, path: &str) -> Vec<&str> {
+        let links = self.get(path).map(|bin| bin.links.as_slice()).unwrap_or_default();
+        links
+            .iter()
+            .filter_map(|link| self.bins.get_key_value(&normalize_path(link)))
+            .map(|(path, _)| path.as_str())
+            .collect()
+    }
+
+    pub fn unresolved_links(&self, path: &str) -> Vec<&str> {
+        let links = self.get(path).map(|bin| bin.links.as_slice()).unwrap_or_default();
+        links
+            .iter()
+            .filter(|link| !self.bins.contains_key(&normalize_path(link)))
+            .map(|link| link.as_str())
+            .collect()
+    }
+
+    pub fn resolve_link(&self, name: &BinFNV) -> Option<(&str, &BinValue)> {
+        let path = self.index.get(name)?;
+        Some((path.as_str(), &self.bins[path].entries[name]))
+    }
+
+    pub fn resolve_link_from(&self, path: &str, name: &BinFNV) -> Option<(&str, &BinValue)> {
+        let mut seen = HashSet::new();
+        let mut pending = VecDeque::new();
+        pending.push_back(normalize_path(path));
+        while let Some(path) = pending.pop_front() {
+            let (path, bin) = match self.bins.get_key_value(&path) {
+                Some(item) => item,
+                None => continue,
+            };
+            if !seen.insert(path.as_str()) {
+                continue;
+            }
+            if let Some(value) = bin.entries.get(name) {
+                return Some((path.as_str(), value));
+            }
+            pending.extend(bin.links.iter().map(|link| normalize_path(link)));
+        }
+        None
+    }
+}
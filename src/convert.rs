@@ -0,0 +1,306 @@
+use crate::*;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::Hash;
+
+fn integer_value(value: &BinValue) -> Option<i128> {
+    match value {
+        BinValue::I8(value) => Some(*value as i128),
+        BinValue::U8(value) => Some(*value as i128),
+        BinValue::I16(value) => Some(*value as i128),
+        BinValue::U16(value) => Some(*value as i128),
+        BinValue::I32(value) => Some(*value as i128),
+        BinValue::U32(value) => Some(*value as i128),
+        BinValue::I64(value) => Some(*value as i128),
+        BinValue::U64(value) => Some(*value as i128),
+        _ => None,
+    }
+}
+
+macro_rules! impl_try_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<&BinValue> for $t {
+                type Error = String;
+
+                fn try_from(value: &BinValue) -> Result<Self, Self::Error> {
+                    let result = match integer_value(value) {
+                        Some(value) => <$t>::try_from(value).ok(),
+                        None => return Err(format!("Expected integer, got {:?}", value)),
+                    };
+                    result.ok_or_else(|| format!("{:?} out of range for {}", value, stringify!($t)))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_try_from_variant {
+    ($($t:ty => $variant:ident),*) => {
+        $(
+            impl TryFrom<&BinValue> for $t {
+                type Error = String;
+
+                fn try_from(value: &BinValue) -> Result<Self, Self::Error> {
+                    match value {
+                        BinValue::$variant(value) => Ok(value.clone()),
+                        _ => Err(format!("Expected {}, got {:?}", stringify!($variant), value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl_try_from_variant!(
+    f32 => Float,
+    [f32; 2] => Vec2,
+    [f32; 3] => Vec3,
+    [f32; 4] => Vec4,
+    [[f32; 4]; 4] => Mtx44,
+    [u8; 4] => Rgba,
+    String => String,
+    BinXXH => File
+);
+
+macro_rules! impl_from_variant {
+    ($($t:ty => $variant:ident),*) => {
+        $(
+            impl From<$t> for BinValue {
+                fn from(value: $t) -> Self {
+                    BinValue::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_variant!(
+    bool => Bool,
+    i8 => I8,
+    u8 => U8,
+    i16 => I16,
+    u16 => U16,
+    i32 => I32,
+    u32 => U32,
+    i64 => I64,
+    u64 => U64,
+    f32 => Float,
+    [f32; 2] => Vec2,
+    [f32; 3] => Vec3,
+    [f32; 4] => Vec4,
+    [[f32; 4]; 4] => Mtx44,
+    [u8; 4] => Rgba,
+    String => String,
+    BinXXH => File
+);
+
+impl From<Vec<BinValue>> for BinValue {
+    fn from(items: Vec<BinValue>) -> Self {
+        BinValue::List(BinType::of_items(items.iter()), items)
+    }
+}
+
+impl From<&str> for BinValue {
+    fn from(value: &str) -> Self {
+        BinValue::String(value.to_string())
+    }
+}
+
+impl TryFrom<&BinValue> for bool {
+    type Error = String;
+
+    fn try_from(value: &BinValue) -> Result<Self, Self::Error> {
+        match value {
+            BinValue::Bool(value) | BinValue::Flag(value) => Ok(*value),
+            _ => Err(format!("Expected Bool or Flag, got {:?}", value)),
+        }
+    }
+}
+
+impl TryFrom<&BinValue> for BinFNV {
+    type Error = String;
+
+    fn try_from(value: &BinValue) -> Result<Self, Self::Error> {
+        match value {
+            BinValue::Hash(value) | BinValue::Link(value) => Ok(*value),
+            _ => Err(format!("Expected Hash or Link, got {:?}", value)),
+        }
+    }
+}
+
+macro_rules! impl_as_integer {
+    ($($name:ident => $t:ty),*) => {
+        $(
+            pub fn $name(&self) -> Option<$t> {
+                <$t>::try_from(self).ok()
+            }
+        )*
+    };
+}
+
+macro_rules! impl_as_variant {
+    ($($name:ident => $t:ty, $variant:ident),*) => {
+        $(
+            pub fn $name(&self) -> Option<$t> {
+                match self {
+                    BinValue::$variant(value) => Some(*value),
+                    _ => None,
+                }
+            }
+        )*
+    };
+}
+
+impl BinValue {
+    impl_as_integer!(
+        as_i8 => i8,
+        as_u8 => u8,
+        as_i16 => i16,
+        as_u16 => u16,
+        as_i32 => i32,
+        as_u32 => u32,
+        as_i64 => i64,
+        as_u64 => u64
+    );
+
+    impl_as_variant!(
+        as_f32 => f32, Float,
+        as_vec2 => [f32; 2], Vec2,
+        as_vec3 => [f32; 3], Vec3,
+        as_vec4 => [f32; 4], Vec4,
+        as_mtx44 => [[f32; 4]; 4], Mtx44,
+        as_rgba => [u8; 4], Rgba
+    );
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            BinValue::Bool(value) | BinValue::Flag(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BinValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_hash(&self) -> Option<&BinFNV> {
+        match self {
+            BinValue::Hash(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_link(&self) -> Option<&BinFNV> {
+        match self {
+            BinValue::Link(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_file(&self) -> Option<&BinXXH> {
+        match self {
+            BinValue::File(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[BinValue]> {
+        match self {
+            BinValue::List(_, items) | BinValue::List2(_, items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&[(BinValue, BinValue)]> {
+        match self {
+            BinValue::Map(_, _, items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_struct(&self) -> Option<(&BinFNV, &IndexMap<BinFNV, BinValue>)> {
+        match self {
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => Some((name, fields)),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, field: &str) -> Option<&BinValue> {
+        let (_, fields) = self.as_struct()?;
+        fields.get(&BinFNV::from_hash(fnv(field)))
+    }
+
+    pub fn map_get_hashed(&self, key: &str) -> Option<&BinValue> {
+        let hash = fnv(key);
+        self.as_map()?
+            .iter()
+            .find(|(item, _)| match item {
+                BinValue::Hash(name) | BinValue::Link(name) => name.get_hash() == hash,
+                BinValue::U32(value) => *value == hash,
+                _ => false,
+            })
+            .map(|(_, value)| value)
+    }
+
+    pub fn as_f64_lossy(&self) -> Option<f64> {
+        match self {
+            BinValue::Bool(value) | BinValue::Flag(value) => Some(if *value { 1.0 } else { 0.0 }),
+            BinValue::Float(value) => Some(*value as f64),
+            _ => integer_value(self).map(|value| value as f64),
+        }
+    }
+
+    pub fn as_i128_checked(&self) -> Option<i128> {
+        match self {
+            BinValue::Bool(value) | BinValue::Flag(value) => Some(*value as i128),
+            BinValue::Float(value) => {
+                let in_range = (i128::MIN as f32..i128::MAX as f32).contains(value);
+                (in_range && value.fract() == 0.0).then_some(*value as i128)
+            }
+            _ => integer_value(self),
+        }
+    }
+
+    pub fn map_as<K, V>(&self) -> Result<HashMap<K, V>, String>
+    where
+        K: for<'a> TryFrom<&'a BinValue, Error = String> + Eq + Hash,
+        V: for<'a> TryFrom<&'a BinValue, Error = String>,
+    {
+        let items = match self {
+            BinValue::Map(_, _, items) => items,
+            _ => return Err(format!("Expected Map, got {:?}", self)),
+        };
+        let mut result = HashMap::with_capacity(items.len());
+        for (index, (key, value)) in items.iter().enumerate() {
+            let k = K::try_from(key)
+                .map_err(|e| format!("Map pair {} key {:?}: {}", index, key, e))?;
+            let v = V::try_from(value)
+                .map_err(|e| format!("Map pair {} value {:?}: {}", index, value, e))?;
+            result.insert(k, v);
+        }
+        Ok(result)
+    }
+
+    pub fn list_as<T>(&self) -> Result<Vec<T>, String>
+    where
+        T: for<'a> TryFrom<&'a BinValue, Error = String>,
+    {
+        let items = match self {
+            BinValue::List(_, items) | BinValue::List2(_, items) => items,
+            _ => return Err(format!("Expected List, got {:?}", self)),
+        };
+        items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                T::try_from(item).map_err(|e| format!("List item {} {:?}: {}", index, item, e))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,169 @@
+use crate::*;
+use std::collections::HashSet;
+
+#[derive(Clone, Debug)]
+pub struct BinFieldChange {
+    pub path: String,
+    pub old: Option<BinValue>,
+    pub new: Option<BinValue>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BinEntryChange {
+    pub name: BinFNV,
+    pub changes: Vec<BinFieldChange>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BinDiff {
+    pub added: Vec<BinFNV>,
+    pub removed: Vec<BinFNV>,
+    pub renamed: Vec<(BinFNV, BinFNV)>,
+    pub changed: Vec<BinEntryChange>,
+}
+
+impl BinDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn name_label(name: &BinFNV) -> String {
+    if name.get_string().is_empty() {
+        format!("0x{:08X}", name.get_hash())
+    } else {
+        name.get_string().to_string()
+    }
+}
+
+fn push_change(path: &str, old: Option<&BinValue>, new: Option<&BinValue>, changes: &mut Vec<BinFieldChange>) {
+    changes.push(BinFieldChange {
+        path: path.to_string(),
+        old: old.cloned(),
+        new: new.cloned(),
+    });
+}
+
+fn diff_fields(
+    path: &str,
+    old: &IndexMap<BinFNV, BinValue>,
+    new: &IndexMap<BinFNV, BinValue>,
+    changes: &mut Vec<BinFieldChange>,
+) {
+    let mut names: Vec<&BinFNV> = old.keys().chain(new.keys().filter(|name| !old.contains_key(*name))).collect();
+    names.sort();
+    for name in names {
+        let path = format!("{}.{}", path, name_label(name));
+        match (old.get(name), new.get(name)) {
+            (Some(old), Some(new)) => diff_values(&path, old, new, changes),
+            (old, new) => push_change(&path, old, new, changes),
+        }
+    }
+}
+
+fn diff_values(path: &str, old: &BinValue, new: &BinValue, changes: &mut Vec<BinFieldChange>) {
+    if old == new {
+        return;
+    }
+    match (old, new) {
+        (BinValue::Struct(old_name, old_fields), BinValue::Struct(new_name, new_fields))
+        | (BinValue::Embed(old_name, old_fields), BinValue::Embed(new_name, new_fields))
+            if old_name == new_name =>
+        {
+            diff_fields(path, old_fields, new_fields, changes)
+        }
+        (BinValue::List(_, old_items), BinValue::List(_, new_items))
+        | (BinValue::List2(_, old_items), BinValue::List2(_, new_items))
+            if old_items.len() == new_items.len() =>
+        {
+            for (index, (old, new)) in old_items.iter().zip(new_items).enumerate() {
+                diff_values(&format!("{}[{}]", path, index), old, new, changes);
+            }
+        }
+        (BinValue::Map(_, _, old_items), BinValue::Map(_, _, new_items)) => {
+            for (key, old) in old_items {
+                let path = format!("{}[{}]", path, key);
+                match new_items.iter().find(|(new_key, _)| new_key == key) {
+                    Some((_, new)) => diff_values(&path, old, new, changes),
+                    None => push_change(&path, Some(old), None, changes),
+                }
+            }
+            let old_keys: HashSet<&BinValue> = old_items.iter().map(|(key, _)| key).collect();
+            for (key, new) in new_items {
+                if !old_keys.contains(key) {
+                    push_change(&format!("{}[{}]", path, key), None, Some(new), changes);
+                }
+            }
+        }
+        _ => push_change(path, Some(old), Some(new), changes),
+    }
+}
+
+fn signature_groups(bin: &Bin, names: &[BinFNV]) -> HashMap<u64, Vec<BinFNV>> {
+    let mut result: HashMap<u64, Vec<BinFNV>> = HashMap::new();
+    for name in names {
+        result.entry(bin.entries[name].signature()).or_default().push(*name);
+    }
+    result
+}
+
+impl Bin {
+    pub fn match_renamed(&self, other: &Bin, removed: &[BinFNV], added: &[BinFNV]) -> Vec<(BinFNV, BinFNV)> {
+        let old = signature_groups(self, removed);
+        let new = signature_groups(other, added);
+        let mut result: Vec<(BinFNV, BinFNV)> = old
+            .iter()
+            .filter_map(|(signature, old)| match (old.as_slice(), new.get(signature)?.as_slice()) {
+                ([old], [new]) => Some((*old, *new)),
+                _ => None,
+            })
+            .collect();
+        result.sort();
+        result
+    }
+
+    pub fn diff(&self, other: &Bin) -> BinDiff {
+        let mut diff = BinDiff::default();
+        let mut names: Vec<&BinFNV> = self.entries.keys().collect();
+        names.sort();
+        for name in names {
+            let old = &self.entries[name];
+            match other.entries.get(name) {
+                Some(new) => {
+                    let mut changes = Vec::new();
+                    diff_values("", old, new, &mut changes);
+                    if !changes.is_empty() {
+                        diff.changed.push(BinEntryChange {
+                            name: *name,
+                            changes,
+                        });
+                    }
+                }
+                None => diff.removed.push(*name),
+            }
+        }
+        let mut added: Vec<BinFNV> = other
+            .entries
+            .keys()
+            .filter(|name| !self.entries.contains_key(*name))
+            .cloned()
+            .collect();
+        added.sort();
+        let renamed = self.match_renamed(other, &diff.removed, &added);
+        for (old_name, new_name) in &renamed {
+            let mut changes = Vec::new();
+            diff_values("", &self.entries[old_name], &other.entries[new_name], &mut changes);
+            if !changes.is_empty() {
+                diff.changed.push(BinEntryChange {
+                    name: *new_name,
+                    changes,
+                });
+            }
+        }
+        diff.removed.retain(|name| !renamed.iter().any(|(old_name, _)| old_name == name));
+        added.retain(|name| !renamed.iter().any(|(_, new_name)| new_name == name));
+        diff.added = added;
+        diff.renamed = renamed;
+        diff
+    }
+}
@@ -0,0 +1,381 @@
+use crate::*;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::mem::discriminant;
+
+fn same_kind(a: &BinValue, b: &BinValue) -> bool {
+    match (a, b) {
+        (BinValue::Struct(a, _), BinValue::Struct(b, _))
+        | (BinValue::Embed(a, _), BinValue::Embed(b, _)) => a == b || a.get_hash() == 0 || b.get_hash() == 0,
+        _ => discriminant(a) == discriminant(b),
+    }
+}
+
+fn fits_type(bin_type: BinType, value: &BinValue) -> bool {
+    match value {
+        BinValue::None => bin_type == BinType::None || bin_type.is_struct(),
+        _ => bin_type == BinType::None || value.bin_type() == bin_type,
+    }
+}
+
+fn coerce(target: &BinValue, new: BinValue) -> Result<BinValue, String> {
+    match target {
+        BinValue::None => match new {
+            BinValue::None | BinValue::Struct(_, _) => Ok(new),
+            _ => Err(format!("Expected Struct, got {:?}", new)),
+        },
+        BinValue::Bool(_) => Ok(BinValue::Bool(bool::try_from(&new)?)),
+        BinValue::Flag(_) => Ok(BinValue::Flag(bool::try_from(&new)?)),
+        BinValue::I8(_) => Ok(BinValue::I8(i8::try_from(&new)?)),
+        BinValue::U8(_) => Ok(BinValue::U8(u8::try_from(&new)?)),
+        BinValue::I16(_) => Ok(BinValue::I16(i16::try_from(&new)?)),
+        BinValue::U16(_) => Ok(BinValue::U16(u16::try_from(&new)?)),
+        BinValue::I32(_) => Ok(BinValue::I32(i32::try_from(&new)?)),
+        BinValue::U32(_) => Ok(BinValue::U32(u32::try_from(&new)?)),
+        BinValue::I64(_) => Ok(BinValue::I64(i64::try_from(&new)?)),
+        BinValue::U64(_) => Ok(BinValue::U64(u64::try_from(&new)?)),
+        BinValue::Float(_) => match new {
+            BinValue::Bool(_) | BinValue::Flag(_) => Err(format!("Expected Float, got {:?}", new)),
+            _ => match new.as_f64_lossy() {
+                Some(value) => Ok(BinValue::Float(value as f32)),
+                None => Err(format!("Expected Float, got {:?}", new)),
+            },
+        },
+        BinValue::List(value_type, items) | BinValue::List2(value_type, items) => match new {
+            BinValue::List(_, new_items) | BinValue::List2(_, new_items) => {
+                if let Some(bad) = new_items.iter().find(|item| !fits_type(*value_type, item)) {
+                    return Err(format!("List item {:?} is not {:?}", bad, value_type));
+                }
+                if let Some(first) = items.first() {
+                    if let Some(bad) = new_items.iter().find(|item| !same_kind(first, item)) {
+                        return Err(format!("List item {:?} does not match {:?}", bad, first));
+                    }
+                }
+                match target {
+                    BinValue::List2(_, _) => Ok(BinValue::List2(*value_type, new_items)),
+                    _ => Ok(BinValue::List(*value_type, new_items)),
+                }
+            }
+            _ => Err(format!("Expected List, got {:?}", new)),
+        },
+        BinValue::Option(value_type, _) => {
+            let value = match new {
+                BinValue::Option(_, value) => value.map(|value| *value),
+                BinValue::None if !value_type.is_struct() => None,
+                value => Some(value),
+            };
+            match value {
+                Some(value) if !fits_type(*value_type, &value) => {
+                    Err(format!("Option value {:?} is not {:?}", value, value_type))
+                }
+                value => Ok(BinValue::Option(*value_type, value.map(Box::new))),
+            }
+        }
+        BinValue::Map(key_type, value_type, items) => match new {
+            BinValue::Map(_, _, new_items) => {
+                let bad = new_items
+                    .iter()
+                    .find(|(key, value)| !fits_type(*key_type, key) || !fits_type(*value_type, value));
+                if let Some((key, value)) = bad {
+                    return Err(format!(
+                        "Map pair {:?}: {:?} is not {:?}: {:?}",
+                        key, value, key_type, value_type
+                    ));
+                }
+                if let Some((first_key, first_value)) = items.first() {
+                    let bad = new_items.iter().find(|(key, value)| {
+                        !same_kind(first_key, key) || !same_kind(first_value, value)
+                    });
+                    if let Some((key, value)) = bad {
+                        return Err(format!(
+                            "Map pair {:?}: {:?} does not match {:?}: {:?}",
+                            key, value, first_key, first_value
+                        ));
+                    }
+                }
+                Ok(BinValue::Map(*key_type, *value_type, new_items))
+            }
+            _ => Err(format!("Expected Map, got {:?}", new)),
+        },
+        _ if same_kind(target, &new) => Ok(new),
+        _ => Err(format!("Can not replace {:?} with {:?}", target, new)),
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(string: &'a str, prefix: &str) -> Option<&'a str> {
+    if string.len() >= prefix.len()
+        && string.is_char_boundary(prefix.len())
+        && string[..prefix.len()].eq_ignore_ascii_case(prefix)
+    {
+        Some(&string[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+fn renamed_path(string: &str, old: &BinFNV, new: &BinFNV) -> Option<String> {
+    if old.get_string().is_empty() || new.get_string().is_empty() {
+        return None;
+    }
+    strip_prefix_ignore_case(string, old.get_string())
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+        .map(|rest| format!("{}{}", new.get_string(), rest))
+}
+
+fn rename_value(value: &mut BinValue, old: &BinFNV, new: &BinFNV) {
+    match value {
+        BinValue::Link(link) => {
+            if link.get_hash() == old.get_hash() {
+                *link = *new;
+            } else if let Some(name) = renamed_path(link.get_string(), old, new) {
+                *link = BinFNV::from_hash_string(fnv(&name), &name);
+            }
+        }
+        BinValue::String(string) => {
+            if let Some(name) = renamed_path(string, old, new) {
+                *string = name;
+            }
+        }
+        BinValue::List(_, items) | BinValue::List2(_, items) => {
+            for item in items {
+                rename_value(item, old, new);
+            }
+        }
+        BinValue::Option(_, Some(item)) => rename_value(item, old, new),
+        BinValue::Map(_, _, items) => {
+            for (key, value) in items {
+                rename_value(key, old, new);
+                rename_value(value, old, new);
+            }
+        }
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+            for value in fields.values_mut() {
+                rename_value(value, old, new);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn sort_key(name: &BinFNV) -> (bool, String, u32) {
+    (name.get_string().is_empty(), name.get_string().to_ascii_lowercase(), name.get_hash())
+}
+
+fn sort_by_name(fields: &mut IndexMap<BinFNV, BinValue>) {
+    fields.sort_by_cached_key(|name, _| sort_key(name));
+}
+
+fn rewrite_file_prefix(value: &mut BinValue, old: &str, new: &str) -> usize {
+    match value {
+        BinValue::File(file) => match strip_prefix_ignore_case(file.get_string(), old) {
+            Some(rest) => {
+                let path = format!("{}{}", new, rest);
+                *file = BinXXH::from_hash_string(xxh64(&path), &path);
+                1
+            }
+            None => 0,
+        },
+        BinValue::List(_, items) | BinValue::List2(_, items) => items
+            .iter_mut()
+            .map(|item| rewrite_file_prefix(item, old, new))
+            .sum(),
+        BinValue::Option(_, Some(item)) => rewrite_file_prefix(item, old, new),
+        BinValue::Map(_, _, items) => items
+            .iter_mut()
+            .map(|(key, value)| rewrite_file_prefix(key, old, new) + rewrite_file_prefix(value, old, new))
+            .sum(),
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => fields
+            .values_mut()
+            .map(|value| rewrite_file_prefix(value, old, new))
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BinRenameMap {
+    pub names: HashMap<u32, BinFNV>,
+}
+
+impl BinRenameMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, old: &str, new: &str) {
+        let old = builder::hashed_name(old).get_hash();
+        self.names.insert(old, BinFNV::from_hash_string(fnv(new), new));
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut result = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once(char::is_whitespace) {
+                Some((old, new)) => result.insert(old, new.trim()),
+                None => return Err(format!("Line {}: expected old and new name", index + 1)),
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn read_from_file(file: File) -> Result<Self, String> {
+        let mut text = String::new();
+        for line in BufReader::new(file).lines() {
+            text.push_str(&line.map_err(|_| "Failed to read line".to_string())?);
+            text.push('\n');
+        }
+        Self::parse(&text)
+    }
+
+    fn rename(&self, name: &mut BinFNV) -> usize {
+        match self.names.get(&name.get_hash()) {
+            Some(new) => {
+                *name = *new;
+                1
+            }
+            None => 0,
+        }
+    }
+
+    fn rename_fields(&self, fields: &mut IndexMap<BinFNV, BinValue>) -> usize {
+        let mut count = 0;
+        *fields = fields
+            .drain(..)
+            .map(|(mut name, mut value)| {
+                count += self.rename(&mut name) + self.rename_value(&mut value);
+                (name, value)
+            })
+            .collect();
+        count
+    }
+
+    fn rename_value(&self, value: &mut BinValue) -> usize {
+        match value {
+            BinValue::Hash(name) | BinValue::Link(name) => self.rename(name),
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                items.iter_mut().map(|item| self.rename_value(item)).sum()
+            }
+            BinValue::Option(_, Some(item)) => self.rename_value(item),
+            BinValue::Map(_, _, items) => items
+                .iter_mut()
+                .map(|(key, value)| self.rename_value(key) + self.rename_value(value))
+                .sum(),
+            BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => self.rename_fields(fields),
+            _ => 0,
+        }
+    }
+}
+
+impl BinValue {
+    pub fn apply_renames(&mut self, map: &BinRenameMap) -> usize {
+        map.rename_value(self)
+    }
+
+    pub fn sort_fields(&mut self) {
+        match self {
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                for item in items {
+                    item.sort_fields();
+                }
+            }
+            BinValue::Option(_, Some(item)) => item.sort_fields(),
+            BinValue::Map(_, _, items) => {
+                for (_, value) in items {
+                    value.sort_fields();
+                }
+            }
+            BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+                sort_by_name(fields);
+                for value in fields.values_mut() {
+                    value.sort_fields();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_in_place(&mut self, new: BinValue) -> Result<(), String> {
+        *self = coerce(self, new)?;
+        Ok(())
+    }
+
+    pub fn set_field(&mut self, name: &str, value: BinValue) -> Result<(), String> {
+        let fields = match self {
+            BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => fields,
+            _ => return Err(format!("Expected Struct, got {:?}", self)),
+        };
+        let name = BinFNV::from_hash_string(fnv(name), name);
+        match fields.get_mut(&name) {
+            Some(field) => field.set_in_place(value),
+            None => {
+                fields.insert(name, value);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Bin {
+    pub fn duplicate_entry(&mut self, name: &str, new_name: &str, rewrite: bool) -> Result<(), String> {
+        let new_key = builder::hashed_name(new_name);
+        if self.entries.contains_key(&new_key) {
+            return Err(format!("Entry {:?} already exists", new_name));
+        }
+        let (old_key, value) = self
+            .entries
+            .get_key_value(&builder::hashed_name(name))
+            .ok_or_else(|| format!("Entry {:?} not found", name))?;
+        let old_key = if old_key.get_string().is_empty() {
+            builder::hashed_name(name)
+        } else {
+            *old_key
+        };
+        let mut value = value.clone();
+        if rewrite {
+            rename_value(&mut value, &old_key, &new_key);
+        }
+        self.entries.insert(new_key, value);
+        Ok(())
+    }
+
+    pub fn apply_renames(&mut self, map: &BinRenameMap) -> usize {
+        let mut count = 0;
+        for link in &mut self.links {
+            if let Some(new) = map.names.get(&fnv(link)) {
+                *link = new.get_string().to_string();
+                count += 1;
+            }
+        }
+        count += map.rename_fields(&mut self.entries);
+        for patch in &mut self.patches {
+            count += map.rename(&mut patch.name) + map.rename_value(&mut patch.value);
+        }
+        count
+    }
+
+    pub fn sort(&mut self) {
+        sort_by_name(&mut self.entries);
+        for value in self.entries.values_mut() {
+            value.sort_fields();
+        }
+        for patch in &mut self.patches {
+            patch.value.sort_fields();
+        }
+    }
+
+    pub fn rewrite_file_prefix(&mut self, old: &str, new: &str) -> usize {
+        let mut count = 0;
+        for value in self.entries.values_mut() {
+            count += rewrite_file_prefix(value, old, new);
+        }
+        for patch in &mut self.patches {
+            count += rewrite_file_prefix(&mut patch.value, old, new);
+        }
+        count
+    }
+}
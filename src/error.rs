@@ -0,0 +1,202 @@
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum BinError {
+    Io(std::io::Error),
+    BadMagic { offset: u64, magic: u32 },
+    UnknownType { offset: u64, type_id: u8 },
+    DepthLimit { offset: u64 },
+    TruncatedString { offset: u64, len: usize },
+    InvalidString { offset: u64 },
+    UnexpectedEof { offset: u64 },
+    TrailingData { offset: u64 },
+    LimitExceeded { offset: u64, what: &'static str, len: usize, limit: usize },
+    EntryNotFound { name: String },
+    StringTooLong { len: usize },
+    TooManyFields { len: usize },
+    MixedTypes,
+    NullValue,
+    EntryNotStruct,
+    UnknownValue,
+    UnsupportedWad { major: u8, minor: u8 },
+    UnsupportedCompression { path: u64, kind: u8 },
+    Text { line: usize, message: String },
+    Json { path: String, message: String },
+    InPath { path: Vec<String>, error: Box<BinError> },
+}
+
+impl BinError {
+    pub(crate) fn in_path(self, segment: String) -> Self {
+        match self {
+            BinError::InPath { mut path, error } => {
+                path.push(segment);
+                BinError::InPath { path, error }
+            }
+            error => BinError::InPath {
+                path: vec![segment],
+                error: Box::new(error),
+            },
+        }
+    }
+
+    pub fn is_unknown_type(&self) -> bool {
+        match self {
+            BinError::UnknownType { .. } => true,
+            BinError::InPath { error, .. } => error.is_unknown_type(),
+            _ => false,
+        }
+    }
+
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            BinError::BadMagic { offset, .. }
+            | BinError::UnknownType { offset, .. }
+            | BinError::DepthLimit { offset }
+            | BinError::TruncatedString { offset, .. }
+            | BinError::InvalidString { offset }
+            | BinError::UnexpectedEof { offset }
+            | BinError::TrailingData { offset }
+            | BinError::LimitExceeded { offset, .. } => Some(*offset),
+            BinError::InPath { error, .. } => error.offset(),
+            _ => None,
+        }
+    }
+
+    pub fn path(&self) -> String {
+        match self {
+            BinError::InPath { path, .. } => path.iter().rev().map(String::as_str).collect(),
+            _ => String::new(),
+        }
+    }
+}
+
+impl Clone for BinError {
+    fn clone(&self) -> Self {
+        match self {
+            BinError::Io(error) => BinError::Io(std::io::Error::new(error.kind(), error.to_string())),
+            BinError::BadMagic { offset, magic } => BinError::BadMagic {
+                offset: *offset,
+                magic: *magic,
+            },
+            BinError::UnknownType { offset, type_id } => BinError::UnknownType {
+                offset: *offset,
+                type_id: *type_id,
+            },
+            BinError::DepthLimit { offset } => BinError::DepthLimit { offset: *offset },
+            BinError::TruncatedString { offset, len } => BinError::TruncatedString {
+                offset: *offset,
+                len: *len,
+            },
+            BinError::InvalidString { offset } => BinError::InvalidString { offset: *offset },
+            BinError::UnexpectedEof { offset } => BinError::UnexpectedEof { offset: *offset },
+            BinError::TrailingData { offset } => BinError::TrailingData { offset: *offset },
+            BinError::LimitExceeded {
+                offset,
+                what,
+                len,
+                limit,
+            } => BinError::LimitExceeded {
+                offset: *offset,
+                what,
+                len: *len,
+                limit: *limit,
+            },
+            BinError::EntryNotFound { name } => BinError::EntryNotFound { name: name.clone() },
+            BinError::StringTooLong { len } => BinError::StringTooLong { len: *len },
+            BinError::TooManyFields { len } => BinError::TooManyFields { len: *len },
+            BinError::MixedTypes => BinError::MixedTypes,
+            BinError::NullValue => BinError::NullValue,
+            BinError::EntryNotStruct => BinError::EntryNotStruct,
+            BinError::UnknownValue => BinError::UnknownValue,
+            BinError::UnsupportedWad { major, minor } => BinError::UnsupportedWad {
+                major: *major,
+                minor: *minor,
+            },
+            BinError::UnsupportedCompression { path, kind } => BinError::UnsupportedCompression {
+                path: *path,
+                kind: *kind,
+            },
+            BinError::Text { line, message } => BinError::Text {
+                line: *line,
+                message: message.clone(),
+            },
+            BinError::Json { path, message } => BinError::Json {
+                path: path.clone(),
+                message: message.clone(),
+            },
+            BinError::InPath { path, error } => BinError::InPath {
+                path: path.clone(),
+                error: error.clone(),
+            },
+        }
+    }
+}
+
+impl Display for BinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinError::Io(error) => write!(f, "{}", error),
+            BinError::BadMagic { offset, magic } => {
+                write!(f, "Bad bin magic 0x{:08X} at offset 0x{:X}", magic, offset)
+            }
+            BinError::UnknownType { offset, type_id } => {
+                write!(f, "Unknown type 0x{:02X} at offset 0x{:X}", type_id, offset)
+            }
+            BinError::DepthLimit { offset } => {
+                write!(f, "Sub reader depth limit reached at offset 0x{:X}", offset)
+            }
+            BinError::TruncatedString { offset, len } => {
+                write!(f, "String of length {} truncated at offset 0x{:X}", len, offset)
+            }
+            BinError::InvalidString { offset } => write!(f, "Invalid UTF-8 string at offset 0x{:X}", offset),
+            BinError::UnexpectedEof { offset } => write!(f, "Unexpected end of data at offset 0x{:X}", offset),
+            BinError::TrailingData { offset } => write!(f, "Unread trailing data at offset 0x{:X}", offset),
+            BinError::LimitExceeded {
+                offset,
+                what,
+                len,
+                limit,
+            } => write!(f, "{} {} exceeds limit {} at offset 0x{:X}", what, len, limit, offset),
+            BinError::EntryNotFound { name } => write!(f, "Entry {} not found", name),
+            BinError::StringTooLong { len } => write!(f, "String of length {} too long", len),
+            BinError::TooManyFields { len } => write!(f, "Too many fields: {}", len),
+            BinError::MixedTypes => write!(f, "Mixed value types in container"),
+            BinError::NullValue => write!(f, "Null value with fields or among non-pointer values"),
+            BinError::EntryNotStruct => write!(f, "Entry must be a struct"),
+            BinError::UnknownValue => write!(f, "Unknown value outside of struct fields"),
+            BinError::UnsupportedWad { major, minor } => write!(f, "Unsupported wad version {}.{}", major, minor),
+            BinError::UnsupportedCompression { path, kind } => {
+                write!(f, "Unsupported compression {} for chunk 0x{:016X}", kind, path)
+            }
+            BinError::Text { line, message } => write!(f, "line {}: {}", line, message),
+            BinError::Json { path, message } if path.is_empty() => write!(f, "{}", message),
+            BinError::Json { path, message } => write!(f, "{}: {}", path, message),
+            BinError::InPath { error, .. } => write!(f, "{} at {}", error, self.path()),
+        }
+    }
+}
+
+impl std::error::Error for BinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BinError::Io(error) => Some(error),
+            BinError::InPath { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for BinError {
+    fn from(error: std::io::Error) -> Self {
+        BinError::Io(error)
+    }
+}
+
+impl From<BinError> for std::io::Error {
+    fn from(error: BinError) -> Self {
+        match error {
+            BinError::Io(error) => error,
+            error => std::io::Error::other(error),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::*;
+
+impl Bin {
+    pub fn find<F>(&self, mut predicate: F) -> Vec<String>
+    where
+        F: FnMut(&BinValue) -> bool,
+    {
+        let mut result = Vec::new();
+        self.walk(|path, value| {
+            if predicate(value) {
+                result.push(path.to_string());
+            }
+            if let BinValue::Map(_, _, items) = value {
+                for (key, _) in items {
+                    if predicate(key) {
+                        result.push(format!("{}[{}]", path, key));
+                    }
+                }
+            }
+        });
+        result
+    }
+
+    pub fn find_by_type(&self, class: &str) -> Vec<String> {
+        let hash = match class.strip_prefix("0x").map(|hex| u32::from_str_radix(hex, 16)) {
+            Some(Ok(hash)) => hash,
+            _ => fnv(class),
+        };
+        self.find(|value| match value {
+            BinValue::Struct(name, _) | BinValue::Embed(name, _) => name.get_hash() == hash,
+            _ => false,
+        })
+    }
+
+    pub fn find_string(&self, needle: &str) -> Vec<String> {
+        self.find(|value| match value {
+            BinValue::String(string) => string.contains(needle),
+            BinValue::File(file) => file.get_string().contains(needle),
+            _ => false,
+        })
+    }
+
+    pub fn find_hash(&self, hash: u64) -> Vec<String> {
+        self.find(|value| match value {
+            BinValue::Hash(name) | BinValue::Link(name) => name.get_hash() as u64 == hash,
+            BinValue::File(file) => file.get_hash() == hash,
+            _ => false,
+        })
+    }
+}
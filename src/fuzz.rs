@@ -0,0 +1,118 @@
+use crate::*;
+use std::convert::TryInto;
+
+fn fuzz_options() -> ReadOptions {
+    ReadOptions {
+        max_string_len: 0x1000,
+        max_list_len: 0x10000,
+        max_map_len: 0x10000,
+        max_fields: 0x1000,
+        max_entries: 0x10000,
+        recover_unknown: true,
+        strict: false,
+        format: BinFormat::Auto,
+    }
+}
+
+pub fn fuzz_target(data: &[u8]) {
+    let hashes = BinHashes::new();
+    if let Ok(bin) = Bin::read_from_data_with_options(data, &hashes, &fuzz_options()) {
+        if let Ok(written) = bin.write_to_data() {
+            let _ = Bin::read_from_data_with_options(&written, &hashes, &fuzz_options());
+        }
+    }
+}
+
+struct EntryLayout<'a> {
+    header: &'a [u8],
+    type_names: Vec<&'a [u8]>,
+    entries: Vec<&'a [u8]>,
+    tail: &'a [u8],
+}
+
+fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Option<&'a [u8]> {
+    let result = data.get(*pos..pos.checked_add(len)?)?;
+    *pos += len;
+    Some(result)
+}
+
+fn take_u32(data: &[u8], pos: &mut usize) -> Option<u32> {
+    Some(u32::from_le_bytes(take(data, pos, 4)?.try_into().ok()?))
+}
+
+fn split_entries(data: &[u8]) -> Option<EntryLayout<'_>> {
+    let mut pos = 0;
+    if take(data, &mut pos, 4)? == b"PTCH" {
+        take(data, &mut pos, 8)?;
+        take(data, &mut pos, 4)?;
+    }
+    let _version = take_u32(data, &mut pos)?;
+    let link_count = take_u32(data, &mut pos)?;
+    for _ in 0..link_count {
+        let len = u16::from_le_bytes(take(data, &mut pos, 2)?.try_into().ok()?);
+        take(data, &mut pos, len as usize)?;
+    }
+    let header = &data[..pos];
+    let count = take_u32(data, &mut pos)? as usize;
+    let mut type_names = Vec::new();
+    for _ in 0..count {
+        type_names.push(take(data, &mut pos, 4)?);
+    }
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let start = pos;
+        let len = take_u32(data, &mut pos)? as usize;
+        take(data, &mut pos, len)?;
+        entries.push(&data[start..pos]);
+    }
+    Some(EntryLayout {
+        header,
+        type_names,
+        entries,
+        tail: &data[pos..],
+    })
+}
+
+fn join_entries(layout: &EntryLayout, keep: &[bool]) -> Vec<u8> {
+    let mut result = layout.header.to_vec();
+    let count = keep.iter().filter(|keep| **keep).count() as u32;
+    result.extend_from_slice(&count.to_le_bytes());
+    for (type_name, keep) in layout.type_names.iter().zip(keep) {
+        if *keep {
+            result.extend_from_slice(type_name);
+        }
+    }
+    for (entry, keep) in layout.entries.iter().zip(keep) {
+        if *keep {
+            result.extend_from_slice(entry);
+        }
+    }
+    result.extend_from_slice(layout.tail);
+    result
+}
+
+pub fn minimize_entries<F>(data: &[u8], still_fails: F) -> Vec<u8>
+where
+    F: Fn(&[u8]) -> bool,
+{
+    let layout = match split_entries(data) {
+        Some(layout) => layout,
+        None => return data.to_vec(),
+    };
+    let mut keep = vec![true; layout.entries.len()];
+    let mut chunk = keep.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < keep.len() {
+            let end = (start + chunk).min(keep.len());
+            let mut candidate = keep.clone();
+            candidate[start..end].iter_mut().for_each(|keep| *keep = false);
+            if candidate != keep && still_fails(&join_entries(&layout, &candidate)) {
+                keep = candidate;
+            }
+            start = end;
+        }
+        chunk /= 2;
+    }
+    join_entries(&layout, &keep)
+}
@@ -0,0 +1,254 @@
+use crate::*;
+use std::collections::{HashSet, VecDeque};
+
+pub(crate) fn collect_links(value: &BinValue, links: &mut Vec<BinFNV>) {
+    match value {
+        BinValue::Link(link) => links.push(*link),
+        BinValue::List(_, items) | BinValue::List2(_, items) => {
+            for item in items {
+                collect_links(item, links);
+            }
+        }
+        BinValue::Option(_, Some(item)) => collect_links(item, links),
+        BinValue::Map(_, _, items) => {
+            for (key, value) in items {
+                collect_links(key, links);
+                collect_links(value, links);
+            }
+        }
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+            for value in fields.values() {
+                collect_links(value, links);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Bin {
+    pub fn closure(&self, root: &BinFNV, max_depth: Option<usize>) -> Vec<BinFNV> {
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        let mut pending = VecDeque::new();
+        pending.push_back((*root, 0));
+        while let Some((name, depth)) = pending.pop_front() {
+            if !seen.insert(name.get_hash()) {
+                continue;
+            }
+            let (name, value) = match self.entries.get_key_value(&name) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            result.push(*name);
+            if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                continue;
+            }
+            let mut links = Vec::new();
+            collect_links(value, &mut links);
+            for link in links {
+                pending.push_back((link, depth + 1));
+            }
+        }
+        result
+    }
+
+    pub fn dead_entries(&self, roots: &[BinFNV]) -> Vec<BinFNV> {
+        let mut alive = HashSet::new();
+        for root in roots {
+            alive.extend(self.closure(root, None));
+        }
+        let mut result: Vec<BinFNV> = self
+            .entries
+            .keys()
+            .filter(|name| !alive.contains(*name))
+            .cloned()
+            .collect();
+        result.sort();
+        result
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BinEntryMetrics {
+    pub path: String,
+    pub name: BinFNV,
+    pub in_degree: usize,
+    pub out_degree: usize,
+    pub dangling: usize,
+    pub component: usize,
+    pub cluster: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BinGraphMetrics {
+    pub entries: Vec<BinEntryMetrics>,
+    pub components: Vec<Vec<BinFNV>>,
+    pub clusters: Vec<Vec<BinFNV>>,
+    pub orphaned: Vec<usize>,
+}
+
+impl BinGraphMetrics {
+    pub fn cycles(&self) -> impl Iterator<Item = &Vec<BinFNV>> {
+        self.components.iter().filter(|component| component.len() > 1)
+    }
+}
+
+fn strong_components(edges: &[Vec<usize>]) -> Vec<usize> {
+    let count = edges.len();
+    let mut index = vec![usize::MAX; count];
+    let mut lowlink = vec![0; count];
+    let mut on_stack = vec![false; count];
+    let mut component = vec![usize::MAX; count];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut next_component = 0;
+    for start in 0..count {
+        if index[start] != usize::MAX {
+            continue;
+        }
+        let mut calls = vec![(start, 0)];
+        index[start] = next_index;
+        lowlink[start] = next_index;
+        next_index += 1;
+        stack.push(start);
+        on_stack[start] = true;
+        while let Some((node, edge)) = calls.last_mut() {
+            let node = *node;
+            if let Some(&child) = edges[node].get(*edge) {
+                *edge += 1;
+                if index[child] == usize::MAX {
+                    index[child] = next_index;
+                    lowlink[child] = next_index;
+                    next_index += 1;
+                    stack.push(child);
+                    on_stack[child] = true;
+                    calls.push((child, 0));
+                } else if on_stack[child] {
+                    lowlink[node] = lowlink[node].min(index[child]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some((parent, _)) = calls.last() {
+                lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+            }
+            if lowlink[node] == index[node] {
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component[member] = next_component;
+                    if member == node {
+                        break;
+                    }
+                }
+                next_component += 1;
+            }
+        }
+    }
+    component
+}
+
+fn weak_clusters(edges: &[Vec<usize>]) -> Vec<usize> {
+    let mut neighbours = vec![Vec::new(); edges.len()];
+    for (node, targets) in edges.iter().enumerate() {
+        for target in targets {
+            neighbours[node].push(*target);
+            neighbours[*target].push(node);
+        }
+    }
+    let mut cluster = vec![usize::MAX; edges.len()];
+    let mut next_cluster = 0;
+    for start in 0..edges.len() {
+        if cluster[start] != usize::MAX {
+            continue;
+        }
+        let mut pending = vec![start];
+        cluster[start] = next_cluster;
+        while let Some(node) = pending.pop() {
+            for neighbour in &neighbours[node] {
+                if cluster[*neighbour] == usize::MAX {
+                    cluster[*neighbour] = next_cluster;
+                    pending.push(*neighbour);
+                }
+            }
+        }
+        next_cluster += 1;
+    }
+    cluster
+}
+
+fn group(names: &[BinFNV], ids: &[usize]) -> Vec<Vec<BinFNV>> {
+    let mut groups = vec![Vec::new(); ids.iter().map(|id| id + 1).max().unwrap_or(0)];
+    for (name, id) in names.iter().zip(ids) {
+        groups[*id].push(*name);
+    }
+    groups
+}
+
+impl BinCollection {
+    pub fn graph_metrics(&self, roots: &[BinFNV]) -> BinGraphMetrics {
+        let mut paths: Vec<&String> = self.bins.keys().collect();
+        paths.sort();
+        let mut nodes = Vec::new();
+        let mut lookup = HashMap::new();
+        for path in paths {
+            for name in self.bins[path].entries.keys() {
+                lookup.entry(*name).or_insert_with(|| {
+                    nodes.push((path, *name));
+                    nodes.len() - 1
+                });
+            }
+        }
+        let mut edges = vec![Vec::new(); nodes.len()];
+        let mut dangling = vec![0; nodes.len()];
+        let mut in_degree = vec![0; nodes.len()];
+        for (node, (path, name)) in nodes.iter().enumerate() {
+            let mut links = Vec::new();
+            collect_links(&self.bins[*path].entries[name], &mut links);
+            for link in links {
+                match lookup.get(&link) {
+                    Some(target) if !edges[node].contains(target) => {
+                        edges[node].push(*target);
+                        in_degree[*target] += 1;
+                    }
+                    Some(_) => {}
+                    None => dangling[node] += 1,
+                }
+            }
+        }
+        let names: Vec<BinFNV> = nodes.iter().map(|(_, name)| *name).collect();
+        let component = strong_components(&edges);
+        let cluster = weak_clusters(&edges);
+        let clusters = group(&names, &cluster);
+        let is_root = |node: usize| {
+            if roots.is_empty() {
+                in_degree[node] == 0
+            } else {
+                roots.contains(&names[node])
+            }
+        };
+        let mut rooted = vec![false; clusters.len()];
+        for node in 0..nodes.len() {
+            if is_root(node) {
+                rooted[cluster[node]] = true;
+            }
+        }
+        BinGraphMetrics {
+            entries: nodes
+                .iter()
+                .enumerate()
+                .map(|(node, (path, name))| BinEntryMetrics {
+                    path: path.to_string(),
+                    name: *name,
+                    in_degree: in_degree[node],
+                    out_degree: edges[node].len(),
+                    dangling: dangling[node],
+                    component: component[node],
+                    cluster: cluster[node],
+                })
+                .collect(),
+            components: group(&names, &component),
+            clusters,
+            orphaned: (0..rooted.len()).filter(|cluster| !rooted[*cluster]).collect(),
+        }
+    }
+}
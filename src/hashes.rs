@@ -1,13 +1,26 @@
 use num_traits::{Num, Unsigned};
 use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
 use std::collections::HashMap;
-use std::fmt::{Debug, Display};
+use std::convert::TryInto;
+use std::fmt::{Debug, Display, LowerHex};
+#[cfg(feature = "hash-lists")]
 use std::fs::File;
 use std::hash::Hash;
-use std::io::{BufRead, BufReader};
+#[cfg(feature = "hash-lists")]
+use std::path::Path;
+#[cfg(feature = "hash-lists")]
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::sync::atomic::{AtomicU8, Ordering as AtomicOrdering};
+use std::sync::Arc;
+
+#[cfg(all(feature = "hash-lists", feature = "tools"))]
+pub mod guesser;
+
+#[cfg(all(feature = "hash-lists", feature = "tools"))]
+pub use guesser::{BinGuess, BinGuessKind, BinHashGuesser};
 
 pub trait BinHashed: Clone + Debug + Eq + Ord + Hash {
-    type HashType: Num + Unsigned + Copy + Display + Debug + Eq + Ord + Hash;
+    type HashType: Num + Unsigned + Copy + Display + Debug + LowerHex + Eq + Ord + Hash;
 
     fn from_hash(hash: Self::HashType) -> Self
     where
@@ -21,6 +34,10 @@ pub trait BinHashed: Clone + Debug + Eq + Ord + Hash {
     where
         Self: Sized;
 
+    fn from_hash_shared(hash: Self::HashType, string: Arc<str>) -> Self
+    where
+        Self: Sized;
+
     fn get_hash(&self) -> Self::HashType;
 
     fn get_string(&self) -> &str;
@@ -28,102 +45,245 @@ pub trait BinHashed: Clone + Debug + Eq + Ord + Hash {
     fn format_to(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
 }
 
-#[derive(Clone)]
-pub struct BinFNV {
-    hash: u32,
-    unhashed: String,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinHashStyle {
+    Hex,
+    Decimal,
+    Wrapped,
 }
 
-impl BinHashed for BinFNV {
-    type HashType = u32;
-
-    fn from_hash(hash: Self::HashType) -> Self {
-        Self {
-            hash,
-            unhashed: String::new(),
-        }
-    }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BinHashDisplay {
+    pub style: BinHashStyle,
+    pub show_hash: bool,
+}
 
-    fn from_string(string: &str) -> Self {
-        let mut hash = 0x811c9dc5u32;
-        for c in string.to_ascii_lowercase().as_bytes() {
-            hash = hash ^ (*c as u32);
-            hash = hash.wrapping_mul(0x01000193u32);
-        }
-        Self {
-            hash,
-            unhashed: String::new(),
-        }
-    }
+static HASH_DISPLAY: AtomicU8 = AtomicU8::new(0);
 
-    fn from_hash_string(hash: Self::HashType, string: &str) -> Self {
+impl BinHashDisplay {
+    pub fn current() -> Self {
+        let bits = HASH_DISPLAY.load(AtomicOrdering::Relaxed);
         Self {
-            hash,
-            unhashed: string.to_string(),
+            style: match bits & 3 {
+                1 => BinHashStyle::Decimal,
+                2 => BinHashStyle::Wrapped,
+                _ => BinHashStyle::Hex,
+            },
+            show_hash: bits & 4 != 0,
         }
     }
 
-    fn get_hash(&self) -> Self::HashType {
-        self.hash
+    pub fn set(self) {
+        let style = match self.style {
+            BinHashStyle::Hex => 0,
+            BinHashStyle::Decimal => 1,
+            BinHashStyle::Wrapped => 2,
+        };
+        HASH_DISPLAY.store(style | if self.show_hash { 4 } else { 0 }, AtomicOrdering::Relaxed);
     }
 
-    fn get_string(&self) -> &str {
-        &self.unhashed
+    pub fn write_hash<W: std::fmt::Write>(&self, f: &mut W, hash: u64, width: usize) -> std::fmt::Result {
+        match self.style {
+            BinHashStyle::Hex => write!(f, "0x{:0width$X}", hash, width = width),
+            BinHashStyle::Decimal => write!(f, "{}", hash),
+            BinHashStyle::Wrapped => write!(f, "{{unknown:0x{:0width$X}}}", hash, width = width),
+        }
     }
 
-    fn format_to(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result  {
-        if self.unhashed.len() != 0 {
-            write!(f, "{:?}", self.unhashed)
+    pub fn write_name<W: std::fmt::Write>(
+        &self,
+        f: &mut W,
+        string: &str,
+        hash: u64,
+        width: usize,
+        quoted: bool,
+    ) -> std::fmt::Result {
+        if string.is_empty() {
+            return self.write_hash(f, hash, width);
+        }
+        if quoted {
+            write!(f, "{:?}", string)?;
         } else {
-            write!(f, "0x{:08X}", self.hash)
+            f.write_str(string)?;
+        }
+        match (self.show_hash, self.style) {
+            (false, _) => Ok(()),
+            (true, BinHashStyle::Decimal) => write!(f, " ({})", hash),
+            (true, _) => write!(f, " (0x{:0width$X})", hash, width = width),
         }
     }
 }
 
-impl Display for BinFNV {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.format_to(f)
+impl Default for BinHashDisplay {
+    fn default() -> Self {
+        Self {
+            style: BinHashStyle::Hex,
+            show_hash: false,
+        }
     }
 }
 
-impl Debug for BinFNV {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.format_to(f)
+const FNV_BASIS: u32 = 0x811c9dc5;
+const FNV_PRIME: u32 = 0x01000193;
+#[cfg(feature = "hash-lists")]
+const FNV_LANES: usize = 8;
+
+pub fn fnv(string: &str) -> u32 {
+    let mut hash = FNV_BASIS;
+    for c in string.as_bytes() {
+        hash ^= c.to_ascii_lowercase() as u32;
+        hash = hash.wrapping_mul(FNV_PRIME);
     }
+    hash
 }
 
-impl Hash for BinFNV {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.get_hash().hash(state)
-    }
+const XXH_PRIME1: u64 = 0x9e3779b185ebca87;
+const XXH_PRIME2: u64 = 0xc2b2ae3d27d4eb4f;
+const XXH_PRIME3: u64 = 0x165667b19e3779f9;
+const XXH_PRIME4: u64 = 0x85ebca77c2b2ae63;
+const XXH_PRIME5: u64 = 0x27d4eb2f165667c5;
+
+fn xxh_round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(XXH_PRIME2))
+        .rotate_left(31)
+        .wrapping_mul(XXH_PRIME1)
 }
 
-impl PartialEq for BinFNV {
-    fn eq(&self, other: &Self) -> bool {
-        self.get_hash() == other.get_hash()
+fn xxh_merge(hash: u64, acc: u64) -> u64 {
+    (hash ^ xxh_round(0, acc))
+        .wrapping_mul(XXH_PRIME1)
+        .wrapping_add(XXH_PRIME4)
+}
+
+pub fn xxh64(string: &str) -> u64 {
+    let data = string.to_ascii_lowercase().into_bytes();
+    let read_u64 = |chunk: &[u8]| u64::from_le_bytes(chunk[..8].try_into().unwrap());
+    let mut rest = data.as_slice();
+    let mut hash = if rest.len() >= 32 {
+        let mut acc = [
+            XXH_PRIME1.wrapping_add(XXH_PRIME2),
+            XXH_PRIME2,
+            0,
+            0u64.wrapping_sub(XXH_PRIME1),
+        ];
+        while rest.len() >= 32 {
+            for (index, acc) in acc.iter_mut().enumerate() {
+                *acc = xxh_round(*acc, read_u64(&rest[index * 8..]));
+            }
+            rest = &rest[32..];
+        }
+        let mut hash = acc[0]
+            .rotate_left(1)
+            .wrapping_add(acc[1].rotate_left(7))
+            .wrapping_add(acc[2].rotate_left(12))
+            .wrapping_add(acc[3].rotate_left(18));
+        for acc in acc {
+            hash = xxh_merge(hash, acc);
+        }
+        hash
+    } else {
+        XXH_PRIME5
+    };
+    hash = hash.wrapping_add(data.len() as u64);
+    while rest.len() >= 8 {
+        hash ^= xxh_round(0, read_u64(rest));
+        hash = hash.rotate_left(27).wrapping_mul(XXH_PRIME1).wrapping_add(XXH_PRIME4);
+        rest = &rest[8..];
     }
+    if rest.len() >= 4 {
+        let value = u32::from_le_bytes(rest[..4].try_into().unwrap()) as u64;
+        hash ^= value.wrapping_mul(XXH_PRIME1);
+        hash = hash.rotate_left(23).wrapping_mul(XXH_PRIME2).wrapping_add(XXH_PRIME3);
+        rest = &rest[4..];
+    }
+    for c in rest {
+        hash ^= (*c as u64).wrapping_mul(XXH_PRIME5);
+        hash = hash.rotate_left(11).wrapping_mul(XXH_PRIME1);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(XXH_PRIME2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(XXH_PRIME3);
+    hash ^= hash >> 32;
+    hash
 }
 
-impl Eq for BinFNV {
-    fn assert_receiver_is_total_eq(&self) {}
+#[cfg(feature = "hash-lists")]
+pub fn fnv_many(strings: &[String]) -> Vec<u32> {
+    LanesFnvBackend.fnv_many(strings)
 }
 
-impl PartialOrd for BinFNV {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.hash.partial_cmp(&other.hash)
+#[cfg(feature = "hash-lists")]
+pub trait FnvBackend {
+    fn fnv_many(&self, strings: &[String]) -> Vec<u32>;
+}
+
+#[cfg(feature = "hash-lists")]
+pub struct ScalarFnvBackend;
+
+#[cfg(feature = "hash-lists")]
+impl FnvBackend for ScalarFnvBackend {
+    fn fnv_many(&self, strings: &[String]) -> Vec<u32> {
+        strings.iter().map(|string| fnv(string)).collect()
     }
 }
 
-impl Ord for BinFNV {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.hash.cmp(&other.hash)
+#[cfg(feature = "hash-lists")]
+pub struct LanesFnvBackend;
+
+#[cfg(feature = "hash-lists")]
+impl FnvBackend for LanesFnvBackend {
+    fn fnv_many(&self, strings: &[String]) -> Vec<u32> {
+        let mut starts = vec![0; strings.iter().map(String::len).max().unwrap_or(0) + 2];
+        for string in strings {
+            starts[string.len() + 1] += 1;
+        }
+        for len in 1..starts.len() {
+            starts[len] += starts[len - 1];
+        }
+        let mut order = vec![0; strings.len()];
+        for (index, string) in strings.iter().enumerate() {
+            order[starts[string.len()]] = index;
+            starts[string.len()] += 1;
+        }
+        let mut result = vec![0; strings.len()];
+        let mut rows = Vec::new();
+        for chunk in order.chunks(FNV_LANES) {
+            let mut lanes = [strings[chunk[0]].as_bytes(); FNV_LANES];
+            for (lane, &index) in lanes.iter_mut().zip(chunk) {
+                *lane = strings[index].as_bytes();
+            }
+            let common = lanes[0].len();
+            rows.clear();
+            rows.resize(common, [0; FNV_LANES]);
+            for (lane, bytes) in lanes.iter().enumerate() {
+                for (row, c) in rows.iter_mut().zip(*bytes) {
+                    row[lane] = c.to_ascii_lowercase();
+                }
+            }
+            let mut hashes = [FNV_BASIS; FNV_LANES];
+            for row in &rows {
+                for (hash, c) in hashes.iter_mut().zip(row) {
+                    *hash = (*hash ^ *c as u32).wrapping_mul(FNV_PRIME);
+                }
+            }
+            for ((hash, bytes), &index) in hashes.iter_mut().zip(&lanes).zip(chunk) {
+                for c in &bytes[common..] {
+                    *hash = (*hash ^ c.to_ascii_lowercase() as u32).wrapping_mul(FNV_PRIME);
+                }
+                result[index] = *hash;
+            }
+        }
+        result
     }
 }
 
+pub type BinFNV = crate::symbol::Name;
+
 #[derive(Clone)]
 pub struct BinXXH {
     hash: u64,
-    unhashed: String,
+    unhashed: Option<Arc<str>>,
 }
 
 impl BinHashed for BinXXH {
@@ -132,18 +292,28 @@ impl BinHashed for BinXXH {
     fn from_hash(hash: Self::HashType) -> Self {
         Self {
             hash,
-            unhashed: String::new(),
+            unhashed: None,
         }
     }
 
     fn from_string(string: &str) -> Self {
-        todo!("Implement this")
+        Self {
+            hash: xxh64(string),
+            unhashed: None,
+        }
     }
 
     fn from_hash_string(hash: Self::HashType, string: &str) -> Self {
         Self {
             hash,
-            unhashed: string.to_string(),
+            unhashed: if string.is_empty() { None } else { Some(Arc::from(string)) },
+        }
+    }
+
+    fn from_hash_shared(hash: Self::HashType, string: Arc<str>) -> Self {
+        Self {
+            hash,
+            unhashed: if string.is_empty() { None } else { Some(string) },
         }
     }
 
@@ -152,18 +322,15 @@ impl BinHashed for BinXXH {
     }
 
     fn get_string(&self) -> &str {
-        &self.unhashed
+        self.unhashed.as_deref().unwrap_or("")
     }
 
     fn format_to(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result  {
-        if self.unhashed.len() != 0 {
-            write!(f, "{:?}", self.unhashed)
-        } else {
-            write!(f, "0x{:016X}", self.hash)
-        }
+        BinHashDisplay::current().write_name(f, self.get_string(), self.hash, 16, true)
     }
 }
 
+#[cfg(feature = "display")]
 impl Display for BinXXH {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.format_to(f)
@@ -188,13 +355,11 @@ impl PartialEq for BinXXH {
     }
 }
 
-impl Eq for BinXXH {
-    fn assert_receiver_is_total_eq(&self) {}
-}
+impl Eq for BinXXH {}
 
 impl PartialOrd for BinXXH {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.hash.partial_cmp(&other.hash)
+        Some(self.cmp(other))
     }
 }
 
@@ -208,7 +373,7 @@ pub struct BinHashList<T>
 where
     T: BinHashed,
 {
-    pub list: HashMap<T::HashType, String>,
+    pub list: HashMap<T::HashType, T>,
 }
 
 impl<T> BinHashList<T>
@@ -221,13 +386,14 @@ where
         }
     }
 
+    #[cfg(feature = "hash-lists")]
     pub fn read_from_file(&mut self, file: File) -> Result<(), String> {
-        for line in BufReader::new(file).lines().into_iter() {
+        for line in BufReader::new(file).lines() {
             let line = line.map_err(|_| "Failed to read line".to_string())?;
             let (hash, unhashed) = match line.split_once(" ") {
                 Some((hash_hex, hash_str)) => {
-                    match T::HashType::from_str_radix(&hash_hex, 16) {
-                        Ok(hash) => Ok((hash, hash_str.to_string())),
+                    match T::HashType::from_str_radix(hash_hex, 16) {
+                        Ok(hash) => Ok((hash, T::from_hash_string(hash, hash_str))),
                         _ => Err("Failed to convert hex".to_string()),
                     }
                 },
@@ -238,15 +404,46 @@ where
         Ok(())
     }
 
+    pub fn insert(&mut self, hash: T::HashType, name: &str) {
+        self.list.insert(hash, T::from_hash_string(hash, name));
+    }
+
+    pub fn add_string(&mut self, name: &str) -> T::HashType {
+        let hash = T::from_string(name).get_hash();
+        self.insert(hash, name);
+        hash
+    }
+
+    #[cfg(feature = "hash-lists")]
+    pub fn write_to_file(&self, file: File) -> Result<(), String> {
+        let width = std::mem::size_of::<T::HashType>() * 2;
+        let mut items: Vec<(&T::HashType, &T)> = self.list.iter().collect();
+        items.sort_by_key(|(hash, _)| **hash);
+        let mut writer = BufWriter::new(file);
+        for (hash, name) in items {
+            writeln!(writer, "{:0width$x} {}", hash, name.get_string(), width = width)
+                .map_err(|_| "Failed to write line".to_string())?;
+        }
+        writer.flush().map_err(|_| "Failed to write file".to_string())
+    }
+
     pub fn get(&self, hash: T::HashType) -> T {
-        if let Some(string) = self.list.get(&hash) {
-            T::from_hash_string(hash, string)
-        } else {
-            T::from_hash(hash)
+        match self.list.get(&hash) {
+            Some(name) => name.clone(),
+            None => T::from_hash(hash),
         }
     }
 }
 
+impl<T> Default for BinHashList<T>
+where
+    T: BinHashed,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct BinHashes {
     pub entries: BinHashList<BinFNV>,
     pub fields: BinHashList<BinFNV>,
@@ -264,4 +461,31 @@ impl BinHashes {
             paths: BinHashList::new(),
         }
     }
+
+    #[cfg(feature = "hash-lists")]
+    pub fn load_from_dir<P: AsRef<Path>>(dir: P) -> Result<BinHashes, String> {
+        let dir = dir.as_ref();
+        let mut hashes = BinHashes::new();
+        let lists = [
+            ("hashes.binentries.txt", &mut hashes.entries),
+            ("hashes.binfields.txt", &mut hashes.fields),
+            ("hashes.binhashes.txt", &mut hashes.hashes),
+            ("hashes.bintypes.txt", &mut hashes.types),
+        ];
+        for (name, list) in lists {
+            if let Ok(file) = File::open(dir.join(name)) {
+                list.read_from_file(file).map_err(|e| format!("{}: {}", name, e))?;
+            }
+        }
+        if let Ok(file) = File::open(dir.join("hashes.game.txt")) {
+            hashes.paths.read_from_file(file).map_err(|e| format!("hashes.game.txt: {}", e))?;
+        }
+        Ok(hashes)
+    }
+}
+
+impl Default for BinHashes {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,216 @@
+use crate::*;
+use std::collections::BTreeSet;
+
+const COMMON_EXTENSIONS: &[&str] = &["anm", "bin", "dds", "png", "scb", "sco", "skl", "skn", "tex", "troybin"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BinGuessKind {
+    Entry,
+    Field,
+    Type,
+    Hash,
+    Path,
+}
+
+#[derive(Clone, Debug)]
+pub struct BinGuess {
+    pub kind: BinGuessKind,
+    pub hash: u64,
+    pub name: String,
+}
+
+impl BinGuess {
+    pub fn insert_into(&self, hashes: &mut BinHashes) {
+        let list = match self.kind {
+            BinGuessKind::Entry => &mut hashes.entries,
+            BinGuessKind::Field => &mut hashes.fields,
+            BinGuessKind::Type => &mut hashes.types,
+            BinGuessKind::Hash => &mut hashes.hashes,
+            BinGuessKind::Path => {
+                hashes.paths.insert(self.hash, &self.name);
+                return;
+            }
+        };
+        list.insert(self.hash as u32, &self.name);
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn split_extension(string: &str) -> Option<(&str, &str)> {
+    string
+        .rsplit_once('.')
+        .filter(|(_, extension)| !extension.is_empty() && !extension.contains('/'))
+}
+
+pub struct BinHashGuesser {
+    pub max_words: usize,
+    candidates: BTreeSet<String>,
+    extensions: BTreeSet<String>,
+    words: BinWordlist,
+    unresolved: BinUnresolved,
+}
+
+impl BinHashGuesser {
+    pub fn new() -> Self {
+        Self {
+            max_words: 64,
+            candidates: BTreeSet::new(),
+            extensions: COMMON_EXTENSIONS.iter().map(|extension| extension.to_string()).collect(),
+            words: BinWordlist::new(),
+            unresolved: BinUnresolved::default(),
+        }
+    }
+
+    pub fn add_string(&mut self, string: &str) {
+        if string.is_empty() || self.candidates.contains(string) {
+            return;
+        }
+        self.words.add_string(string);
+        self.candidates.insert(string.to_string());
+        if let Some((stem, extension)) = split_extension(string) {
+            self.candidates.insert(stem.to_string());
+            self.extensions.insert(extension.to_ascii_lowercase());
+        }
+        for (index, _) in string.match_indices('/') {
+            self.candidates.insert(string[..index].to_string());
+            self.candidates.insert(string[index + 1..].to_string());
+        }
+        let rest = string
+            .get(..5)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("data/"))
+            .and_then(|_| string.get(5..));
+        if let Some(rest) = rest {
+            self.candidates.insert(rest.to_string());
+            if let Some((stem, _)) = split_extension(rest) {
+                self.candidates.insert(stem.to_string());
+            }
+        }
+    }
+
+    fn add_value(&mut self, value: &BinValue) {
+        match value {
+            BinValue::String(value) => self.add_string(value),
+            BinValue::Hash(name) | BinValue::Link(name) => self.add_string(name.get_string()),
+            BinValue::File(file) => self.add_string(file.get_string()),
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                for item in items {
+                    self.add_value(item);
+                }
+            }
+            BinValue::Option(_, Some(item)) => self.add_value(item),
+            BinValue::Map(_, _, items) => {
+                for (key, value) in items {
+                    self.add_value(key);
+                    self.add_value(value);
+                }
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                self.add_string(name.get_string());
+                for (name, value) in fields {
+                    self.add_string(name.get_string());
+                    self.add_value(value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn add_bin(&mut self, bin: &Bin) {
+        self.unresolved.merge(&bin.unresolved_hashes());
+        for link in &bin.links {
+            self.add_string(link);
+        }
+        for (name, value) in &bin.entries {
+            self.add_string(name.get_string());
+            self.add_value(value);
+        }
+        for patch in &bin.patches {
+            self.add_string(patch.name.get_string());
+            self.add_value(&patch.value);
+        }
+    }
+
+    pub fn unknown_count(&self) -> usize {
+        self.unresolved.len()
+    }
+
+    fn word_candidates(&self) -> Vec<String> {
+        let words: Vec<String> = self
+            .words
+            .ranked()
+            .into_iter()
+            .take(self.max_words)
+            .map(|(word, _)| capitalize(&word))
+            .collect();
+        let mut result = Vec::new();
+        for first in &words {
+            result.push(first.clone());
+            result.push(format!("m{}", first));
+            for second in &words {
+                result.push(format!("{}{}", first, second));
+                result.push(format!("m{}{}", first, second));
+            }
+        }
+        result
+    }
+
+    fn path_candidates(&self) -> Vec<String> {
+        let mut result = Vec::new();
+        for candidate in self.candidates.iter().filter(|candidate| candidate.contains('/')) {
+            let stem = split_extension(candidate).map_or(candidate.as_str(), |(stem, _)| stem);
+            for extension in &self.extensions {
+                result.push(format!("{}.{}", stem, extension));
+            }
+        }
+        result
+    }
+
+    pub fn guess(&self) -> Vec<BinGuess> {
+        let mut found: BTreeSet<(BinGuessKind, u64, String)> = BTreeSet::new();
+        let names = self.candidates.iter().cloned().chain(self.word_candidates());
+        for name in names {
+            let hash = fnv(&name);
+            let kinds = [
+                (BinGuessKind::Entry, &self.unresolved.entries),
+                (BinGuessKind::Field, &self.unresolved.fields),
+                (BinGuessKind::Type, &self.unresolved.types),
+                (BinGuessKind::Hash, &self.unresolved.hashes),
+            ];
+            for (kind, unknown) in kinds {
+                if unknown.contains(&hash) {
+                    found.insert((kind, hash as u64, name.clone()));
+                }
+            }
+        }
+        if !self.unresolved.paths.is_empty() {
+            let paths = self.candidates.iter().cloned().chain(self.path_candidates());
+            for path in paths {
+                let hash = xxh64(&path);
+                if self.unresolved.paths.contains(&hash) {
+                    found.insert((BinGuessKind::Path, hash, path.to_ascii_lowercase()));
+                }
+            }
+        }
+        let mut result: Vec<BinGuess> = Vec::new();
+        for (kind, hash, name) in found {
+            if result.last().is_some_and(|last| last.kind == kind && last.hash == hash) {
+                continue;
+            }
+            result.push(BinGuess { kind, hash, name });
+        }
+        result
+    }
+}
+
+impl Default for BinHashGuesser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
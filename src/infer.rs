@@ -0,0 +1,98 @@
+use crate::*;
+use std::cmp::Reverse;
+use std::convert::TryFrom;
+
+#[derive(Clone, Debug)]
+pub struct BinUnknownGuess {
+    pub bin_type: BinType,
+    pub value: BinValue,
+    pub len: usize,
+}
+
+fn plausible_float(value: f32) -> bool {
+    value == 0.0 || (value.is_finite() && (1e-5..=1e6).contains(&value.abs()))
+}
+
+fn plausible_string(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| !c.is_control())
+}
+
+fn plausible_name(name: &BinFNV) -> bool {
+    !name.get_string().is_empty()
+}
+
+fn plausible(value: &BinValue) -> bool {
+    match value {
+        _ if value.is_null() => false,
+        BinValue::I8(value) => *value < 0,
+        BinValue::I16(value) => *value < 0,
+        BinValue::I32(value) => *value < 0,
+        BinValue::I64(value) => *value < 0,
+        BinValue::Float(value) => plausible_float(*value),
+        BinValue::Vec2(value) => value.iter().all(|value| plausible_float(*value)),
+        BinValue::Vec3(value) => value.iter().all(|value| plausible_float(*value)),
+        BinValue::Vec4(value) => value.iter().all(|value| plausible_float(*value)),
+        BinValue::Mtx44(value) => value.iter().flatten().all(|value| plausible_float(*value)),
+        BinValue::Rgba(_) => false,
+        BinValue::String(value) => plausible_string(value),
+        BinValue::Hash(name) | BinValue::Link(name) => plausible_name(name),
+        BinValue::File(file) => !file.get_string().is_empty(),
+        BinValue::List(_, items) | BinValue::List2(_, items) => items.iter().all(plausible),
+        BinValue::Option(_, value) => value.iter().all(|value| plausible(value)),
+        BinValue::Map(_, _, items) => items.iter().all(|(key, value)| plausible(key) && plausible(value)),
+        _ => true,
+    }
+}
+
+fn continues(rest: &[u8]) -> bool {
+    rest.is_empty() || rest.get(4).is_some_and(|type_id| BinType::try_from(*type_id).is_ok())
+}
+
+pub fn guess_raw_value(data: &[u8], hashes: &BinHashes) -> Vec<BinUnknownGuess> {
+    let options = ReadOptions {
+        max_list_len: data.len(),
+        max_map_len: data.len(),
+        max_fields: data.len(),
+        ..ReadOptions::default()
+    };
+    let mut result = Vec::new();
+    for type_id in 0..=u8::MAX {
+        let bin_type = match BinType::try_from(type_id) {
+            Ok(bin_type) => bin_type,
+            Err(_) => continue,
+        };
+        let (value, len) = match BinReader::try_read_value(data, hashes, &options, bin_type) {
+            Some(read) => read,
+            None => continue,
+        };
+        let structured = bin_type.is_container() || bin_type.is_struct() || bin_type == BinType::String;
+        let fits = if structured { continues(&data[len..]) } else { len == data.len() };
+        if fits && plausible(&value) {
+            result.push(BinUnknownGuess { bin_type, value, len });
+        }
+    }
+    if data.len() > 4 && data.len().is_multiple_of(4) {
+        let floats: Vec<f32> = data
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        if floats.iter().all(|value| plausible_float(*value)) {
+            result.push(BinUnknownGuess {
+                bin_type: BinType::List,
+                value: BinValue::List(BinType::F32, floats.into_iter().map(BinValue::Float).collect()),
+                len: data.len(),
+            });
+        }
+    }
+    result.sort_by_key(|guess| (guess.len != data.len(), Reverse(guess.len)));
+    result
+}
+
+impl BinValue {
+    pub fn guess_unknown(&self, hashes: &BinHashes) -> Vec<BinUnknownGuess> {
+        match self {
+            BinValue::Unknown(_, bytes, _) => guess_raw_value(bytes, hashes),
+            _ => Vec::new(),
+        }
+    }
+}
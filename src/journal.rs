@@ -0,0 +1,101 @@
+use crate::*;
+
+#[derive(Clone, Debug)]
+pub struct BinEdit {
+    pub entry: BinFNV,
+    pub path: String,
+    pub old: BinValue,
+    pub new: BinValue,
+}
+
+pub struct BinEditSession {
+    pub bin: Bin,
+    undo: Vec<BinEdit>,
+    redo: Vec<BinEdit>,
+}
+
+impl BinEditSession {
+    pub fn new(bin: Bin) -> Self {
+        Self {
+            bin,
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    fn target(&mut self, entry: &BinFNV, path: &str) -> Result<&mut BinValue, String> {
+        self.bin
+            .entries
+            .get_mut(entry)
+            .and_then(|value| value.select_mut(path))
+            .ok_or_else(|| format!("Path {}{} not found", entry, path))
+    }
+
+    pub fn set(&mut self, query: &str, value: BinValue) -> Result<(), String> {
+        let end = query.find(['.', '[']).unwrap_or(query.len());
+        let (entry, path) = query.split_at(end);
+        let entry = match entry.strip_prefix("0x").map(|hex| u32::from_str_radix(hex, 16)) {
+            Some(Ok(hash)) => BinFNV::from_hash(hash),
+            _ => BinFNV::from_hash_string(fnv(entry), entry),
+        };
+        let target = self.target(&entry, path)?;
+        let old = target.clone();
+        target.set_in_place(value)?;
+        let new = target.clone();
+        self.undo.push(BinEdit {
+            entry,
+            path: path.to_string(),
+            old,
+            new,
+        });
+        self.redo.clear();
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Result<bool, String> {
+        let edit = match self.undo.pop() {
+            Some(edit) => edit,
+            None => return Ok(false),
+        };
+        *self.target(&edit.entry, &edit.path)? = edit.old.clone();
+        self.redo.push(edit);
+        Ok(true)
+    }
+
+    pub fn redo(&mut self) -> Result<bool, String> {
+        let edit = match self.redo.pop() {
+            Some(edit) => edit,
+            None => return Ok(false),
+        };
+        *self.target(&edit.entry, &edit.path)? = edit.new.clone();
+        self.undo.push(edit);
+        Ok(true)
+    }
+
+    pub fn journal(&self) -> &[BinEdit] {
+        &self.undo
+    }
+
+    pub fn to_diff(&self) -> BinDiff {
+        let mut diff = BinDiff::default();
+        for edit in &self.undo {
+            let change = BinFieldChange {
+                path: edit.path.clone(),
+                old: Some(edit.old.clone()),
+                new: Some(edit.new.clone()),
+            };
+            match diff.changed.iter_mut().find(|entry| entry.name == edit.entry) {
+                Some(entry) => entry.changes.push(change),
+                None => diff.changed.push(BinEntryChange {
+                    name: edit.entry,
+                    changes: vec![change],
+                }),
+            }
+        }
+        diff
+    }
+
+    pub fn finish(self) -> Bin {
+        self.bin
+    }
+}
@@ -0,0 +1,881 @@
+use crate::*;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+type Result<T> = std::result::Result<T, BinError>;
+
+fn json_error(message: impl Into<String>) -> BinError {
+    BinError::Json {
+        path: String::new(),
+        message: message.into(),
+    }
+}
+
+fn in_json_path(error: BinError, segment: &str) -> BinError {
+    match error {
+        BinError::Json { path, message } => BinError::Json {
+            path: format!("/{}{}", segment, path),
+            message,
+        },
+        error => error,
+    }
+}
+
+fn in_value(error: BinError) -> BinError {
+    in_json_path(error, "value")
+}
+
+fn fnv_to_json(name: &BinFNV) -> Value {
+    if name.get_string().is_empty() {
+        Value::String(format!("0x{:08X}", name.get_hash()))
+    } else {
+        Value::String(name.get_string().to_string())
+    }
+}
+
+fn xxh_to_json(name: &BinXXH) -> Value {
+    if name.get_string().is_empty() {
+        Value::String(format!("0x{:016X}", name.get_hash()))
+    } else {
+        Value::String(name.get_string().to_string())
+    }
+}
+
+fn fnv_to_key(name: &BinFNV) -> String {
+    match fnv_to_json(name) {
+        Value::String(key) => key,
+        _ => unreachable!(),
+    }
+}
+
+fn parse_hex(string: &str) -> Option<u64> {
+    u64::from_str_radix(string.strip_prefix("0x")?, 16).ok()
+}
+
+fn fnv_from_str(string: &str) -> Result<BinFNV> {
+    match parse_hex(string) {
+        Some(hash) if hash <= u32::MAX as u64 => Ok(BinFNV::from_hash(hash as u32)),
+        Some(_) => Err(json_error(format!("Hash {} out of range", string))),
+        None => Ok(BinFNV::from_hash_string(fnv(string), string)),
+    }
+}
+
+fn fnv_from_json(value: &Value) -> Result<BinFNV> {
+    fnv_from_str(value.as_str().ok_or_else(|| json_error("Expected hash string"))?)
+}
+
+fn xxh_from_json(value: &Value) -> Result<BinXXH> {
+    let string = value.as_str().ok_or_else(|| json_error("Expected path string"))?;
+    match parse_hex(string) {
+        Some(hash) => Ok(BinXXH::from_hash(hash)),
+        None => Ok(BinXXH::from_hash_string(xxh64(string), string)),
+    }
+}
+
+fn type_from_json(value: &Value) -> Result<Option<BinType>> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(name) => match BinType::from_name(name) {
+            Some(bin_type) => Ok(Some(bin_type)),
+            None => Err(json_error(format!("Unknown value type {:?}", name))),
+        },
+        _ => Err(json_error("Expected type name")),
+    }
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(value: &Value) -> Result<T> {
+    serde_json::from_value(value.clone()).map_err(|e| json_error(e.to_string()))
+}
+
+fn fields_from_json(value: &Value) -> Result<IndexMap<BinFNV, BinValue>> {
+    let fields = value
+        .as_object()
+        .ok_or_else(|| json_error("Expected fields object"))?;
+    let mut result = IndexMap::new();
+    for (name, value) in fields {
+        let segment = escape_pointer(name);
+        let value = BinValue::from_json(value).map_err(|e| in_json_path(e, &segment))?;
+        result.insert(fnv_from_str(name).map_err(|e| in_json_path(e, &segment))?, value);
+    }
+    Ok(result)
+}
+
+fn escape_pointer(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn numbers_match(a: &serde_json::Number, b: &serde_json::Number) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        _ if a == b => true,
+        (Some(a), Some(b)) => (a - b).abs() <= 1e-6 * a.abs().max(b.abs()).max(1.0),
+        _ => false,
+    }
+}
+
+const WRAPPER_KEYS: [&str; 7] = ["type", "value", "value_type", "key_type", "name", "type_id", "trailing_fields"];
+
+fn is_wrapper(object: &Map<String, Value>) -> bool {
+    object.get("type").is_some_and(Value::is_string)
+        && object.contains_key("value")
+        && object.keys().all(|key| WRAPPER_KEYS.contains(&key.as_str()))
+}
+
+fn plain_key(key: Value) -> String {
+    match key {
+        Value::String(key) => key,
+        key => key.to_string(),
+    }
+}
+
+fn plain_json(value: &Value) -> Value {
+    match value {
+        Value::Object(object) if is_wrapper(object) => match (&object["type"], &object["value"]) {
+            (Value::String(kind), Value::Array(pairs))
+                if kind == "map" && pairs.iter().all(|pair| pair.as_array().is_some_and(|pair| pair.len() == 2)) =>
+            {
+                Value::Object(
+                    pairs
+                        .iter()
+                        .map(|pair| (plain_key(plain_json(&pair[0])), plain_json(&pair[1])))
+                        .collect(),
+                )
+            }
+            (_, value) => plain_json(value),
+        },
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), plain_json(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(plain_json).collect()),
+        _ => value.clone(),
+    }
+}
+
+fn names_match(a: &str, b: &str) -> bool {
+    match (parse_hex(a), parse_hex(b)) {
+        (Some(a), Some(b)) => a == b,
+        (Some(hash), None) => hash == fnv(b) as u64 || hash == xxh64(b),
+        (None, Some(hash)) => hash == fnv(a) as u64 || hash == xxh64(a),
+        (None, None) => a == b,
+    }
+}
+
+fn find_key<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    object
+        .get(key)
+        .or_else(|| object.iter().find(|(other, _)| names_match(key, other)).map(|(_, value)| value))
+}
+
+fn compare_json_at(path: &str, ours: &Value, reference: &Value, result: &mut Vec<(String, String)>) {
+    match (ours, reference) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a {
+                let path = format!("{}/{}", path, escape_pointer(key));
+                match find_key(b, key) {
+                    Some(other) => compare_json_at(&path, value, other, result),
+                    None => result.push((path, "missing in reference".to_string())),
+                }
+            }
+            for key in b.keys().filter(|key| find_key(a, key).is_none()) {
+                let path = format!("{}/{}", path, escape_pointer(key));
+                result.push((path, "missing in ours".to_string()));
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            if a.len() != b.len() {
+                let message = format!("length {} != {}", a.len(), b.len());
+                result.push((path.to_string(), message));
+            }
+            for (index, (a, b)) in a.iter().zip(b).enumerate() {
+                compare_json_at(&format!("{}/{}", path, index), a, b, result);
+            }
+        }
+        (Value::Number(a), Value::Number(b)) if numbers_match(a, b) => {}
+        (Value::String(a), Value::String(b)) if names_match(a, b) => {}
+        _ if ours == reference => {}
+        _ => result.push((path.to_string(), format!("{} != {}", ours, reference))),
+    }
+}
+
+pub fn compare_json(ours: &Value, reference: &Value) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    compare_json_at("", &plain_json(ours), &plain_json(reference), &mut result);
+    result
+}
+
+fn check_hash(path: &str, value: &Value, max: u64, errors: &mut Vec<(String, String)>) {
+    match value.as_str() {
+        Some(string) if string.starts_with("0x") => match parse_hex(string) {
+            Some(hash) if hash <= max => {}
+            _ => errors.push((path.to_string(), format!("Malformed hash {:?}", string))),
+        },
+        Some(_) => {}
+        None => errors.push((path.to_string(), "Expected hash string".to_string())),
+    }
+}
+
+fn check_numbers(path: &str, value: &Value, count: usize, errors: &mut Vec<(String, String)>) {
+    match value.as_array() {
+        Some(items) if items.len() == count && items.iter().all(|item| item.is_number()) => {}
+        _ => errors.push((path.to_string(), format!("Expected {} numbers", count))),
+    }
+}
+
+fn check_integer(path: &str, value: &Value, min: i128, max: i128, errors: &mut Vec<(String, String)>) {
+    let number = value
+        .as_i64()
+        .map(|value| value as i128)
+        .or_else(|| value.as_u64().map(|value| value as i128));
+    match number {
+        Some(number) if number >= min && number <= max => {}
+        Some(number) => errors.push((path.to_string(), format!("{} out of range", number))),
+        None => errors.push((path.to_string(), "Expected integer".to_string())),
+    }
+}
+
+fn check_type_name(path: &str, value: &Value, errors: &mut Vec<(String, String)>) {
+    match value {
+        Value::Null => {}
+        Value::String(name) if BinType::from_name(name).is_some() => {}
+        _ => errors.push((path.to_string(), format!("Unknown value type {}", value))),
+    }
+}
+
+fn check_items_type<'v, I>(path: &str, items: I, errors: &mut Vec<(String, String)>)
+where
+    I: Iterator<Item = &'v Value>,
+{
+    let mut first = None;
+    for item in items {
+        match item["type"].as_str() {
+            Some("none") | None => {}
+            Some(type_name) => match first {
+                None => first = Some(type_name),
+                Some(first) if first != type_name => {
+                    errors.push((path.to_string(), format!("Mixed item types {} and {}", first, type_name)));
+                    return;
+                }
+                Some(_) => {}
+            },
+        }
+    }
+}
+
+fn validate_value(path: &str, json: &Value, errors: &mut Vec<(String, String)>) {
+    let type_name = match json["type"].as_str() {
+        Some(type_name) => type_name,
+        None => {
+            errors.push((path.to_string(), "Missing value type".to_string()));
+            return;
+        }
+    };
+    let value_path = format!("{}/value", path);
+    let value = &json["value"];
+    match type_name {
+        "none" => {}
+        "bool" | "flag" if value.is_boolean() => {}
+        "bool" | "flag" => errors.push((value_path, "Expected bool".to_string())),
+        "i8" => check_integer(&value_path, value, i8::MIN as i128, i8::MAX as i128, errors),
+        "u8" => check_integer(&value_path, value, 0, u8::MAX as i128, errors),
+        "i16" => check_integer(&value_path, value, i16::MIN as i128, i16::MAX as i128, errors),
+        "u16" => check_integer(&value_path, value, 0, u16::MAX as i128, errors),
+        "i32" => check_integer(&value_path, value, i32::MIN as i128, i32::MAX as i128, errors),
+        "u32" => check_integer(&value_path, value, 0, u32::MAX as i128, errors),
+        "i64" => check_integer(&value_path, value, i64::MIN as i128, i64::MAX as i128, errors),
+        "u64" => check_integer(&value_path, value, 0, u64::MAX as i128, errors),
+        "f32" if value.is_number() => {}
+        "f32" => errors.push((value_path, "Expected number".to_string())),
+        "vec2" => check_numbers(&value_path, value, 2, errors),
+        "vec3" => check_numbers(&value_path, value, 3, errors),
+        "vec4" => check_numbers(&value_path, value, 4, errors),
+        "mtx44" => match value.as_array() {
+            Some(rows) if rows.len() == 4 => {
+                for (index, row) in rows.iter().enumerate() {
+                    check_numbers(&format!("{}/{}", value_path, index), row, 4, errors);
+                }
+            }
+            _ => errors.push((value_path, "Expected 4 rows".to_string())),
+        },
+        "rgba" => match value.as_array() {
+            Some(items) if items.len() == 4 => {
+                for (index, item) in items.iter().enumerate() {
+                    check_integer(&format!("{}/{}", value_path, index), item, 0, u8::MAX as i128, errors);
+                }
+            }
+            _ => errors.push((value_path, "Expected 4 integers".to_string())),
+        },
+        "unknown" => {
+            check_integer(&format!("{}/type_id", path), &json["type_id"], 0, u8::MAX as i128, errors);
+            if !json["trailing_fields"].is_null() {
+                let trailing_path = format!("{}/trailing_fields", path);
+                check_integer(&trailing_path, &json["trailing_fields"], 0, u16::MAX as i128, errors);
+            }
+            match value.as_array() {
+                Some(bytes) => {
+                    for (index, byte) in bytes.iter().enumerate() {
+                        check_integer(&format!("{}/{}", value_path, index), byte, 0, u8::MAX as i128, errors);
+                    }
+                }
+                None => errors.push((value_path, "Expected byte array".to_string())),
+            }
+        }
+        "string" if value.is_string() => {}
+        "string" => errors.push((value_path, "Expected string".to_string())),
+        "hash" | "link" => check_hash(&value_path, value, u32::MAX as u64, errors),
+        "file" => check_hash(&value_path, value, u64::MAX, errors),
+        "option" => {
+            check_type_name(&format!("{}/value_type", path), &json["value_type"], errors);
+            if !value.is_null() {
+                validate_value(&value_path, value, errors);
+            }
+        }
+        "list" | "list2" => match value.as_array() {
+            Some(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(&format!("{}/{}", value_path, index), item, errors);
+                }
+                check_items_type(&value_path, items.iter(), errors);
+                check_type_name(&format!("{}/value_type", path), &json["value_type"], errors);
+            }
+            None => errors.push((value_path, "Expected list array".to_string())),
+        },
+        "map" => match value.as_array() {
+            Some(items) => {
+                let mut pairs = Vec::new();
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = format!("{}/{}", value_path, index);
+                    match item.as_array().map(|pair| pair.as_slice()) {
+                        Some([key, value]) => {
+                            validate_value(&format!("{}/0", item_path), key, errors);
+                            validate_value(&format!("{}/1", item_path), value, errors);
+                            pairs.push((key, value));
+                        }
+                        _ => errors.push((item_path, "Expected pair".to_string())),
+                    }
+                }
+                check_items_type(&value_path, pairs.iter().map(|(key, _)| *key), errors);
+                check_items_type(&value_path, pairs.iter().map(|(_, value)| *value), errors);
+                check_type_name(&format!("{}/key_type", path), &json["key_type"], errors);
+                check_type_name(&format!("{}/value_type", path), &json["value_type"], errors);
+            }
+            None => errors.push((value_path, "Expected map array".to_string())),
+        },
+        "pointer" | "embed" => {
+            check_hash(&format!("{}/name", path), &json["name"], u32::MAX as u64, errors);
+            match value.as_object() {
+                Some(fields) => {
+                    for (name, field) in fields {
+                        let field_path = format!("{}/{}", value_path, escape_pointer(name));
+                        check_hash(&field_path, &Value::String(name.clone()), u32::MAX as u64, errors);
+                        validate_value(&field_path, field, errors);
+                    }
+                }
+                None if json["name"] == "0x00000000" => {}
+                None => errors.push((value_path, "Expected fields object".to_string())),
+            }
+        }
+        _ => errors.push((format!("{}/type", path), format!("Unknown value type {:?}", type_name))),
+    }
+}
+
+pub fn validate_json(json: &Value) -> Vec<(String, String)> {
+    let mut errors = Vec::new();
+    match json["type"].as_str() {
+        Some("PROP") | Some("PTCH") => {}
+        _ => errors.push(("/type".to_string(), "Bad bin type".to_string())),
+    }
+    if json["version"].as_u64().is_none_or(|version| version > u32::MAX as u64) {
+        errors.push(("/version".to_string(), "Expected u32 version".to_string()));
+    }
+    match json["links"].as_array() {
+        Some(links) => {
+            for (index, link) in links.iter().enumerate() {
+                if !link.is_string() {
+                    errors.push((format!("/links/{}", index), "Expected string".to_string()));
+                }
+            }
+        }
+        None => errors.push(("/links".to_string(), "Expected links array".to_string())),
+    }
+    match json["entries"].as_object() {
+        Some(entries) => {
+            for (name, value) in entries {
+                let path = format!("/entries/{}", escape_pointer(name));
+                check_hash(&path, &Value::String(name.clone()), u32::MAX as u64, &mut errors);
+                match value["type"].as_str() {
+                    Some("pointer") | Some("embed") => validate_value(&path, value, &mut errors),
+                    _ => errors.push((path, "Entry must be a struct".to_string())),
+                }
+            }
+        }
+        None => errors.push(("/entries".to_string(), "Expected entries object".to_string())),
+    }
+    if let Some(patches) = json["patches"].as_array() {
+        for (index, patch) in patches.iter().enumerate() {
+            let path = format!("/patches/{}", index);
+            check_hash(&format!("{}/name", path), &patch["name"], u32::MAX as u64, &mut errors);
+            if !patch["path"].is_string() {
+                errors.push((format!("{}/path", path), "Expected string".to_string()));
+            }
+            validate_value(&format!("{}/value", path), &patch["value"], &mut errors);
+        }
+    }
+    errors
+}
+
+/// Serializes a value in the JSON export layout, keeping fields in file order.
+pub struct BinJson<'a>(pub &'a BinValue);
+
+struct FieldsJson<'a>(&'a IndexMap<BinFNV, BinValue>);
+
+struct PatchJson<'a>(&'a BinPatch);
+
+struct BinFileJson<'a>(&'a Bin);
+
+fn serialize_scalar<M: SerializeMap, T: Serialize + ?Sized>(
+    map: &mut M,
+    type_name: &str,
+    value: &T,
+) -> std::result::Result<(), M::Error> {
+    map.serialize_entry("type", type_name)?;
+    map.serialize_entry("value", value)
+}
+
+impl Serialize for BinJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match self.0 {
+            BinValue::None => serialize_scalar(&mut map, "none", &())?,
+            BinValue::Bool(value) => serialize_scalar(&mut map, "bool", value)?,
+            BinValue::Flag(value) => serialize_scalar(&mut map, "flag", value)?,
+            BinValue::I8(value) => serialize_scalar(&mut map, "i8", value)?,
+            BinValue::U8(value) => serialize_scalar(&mut map, "u8", value)?,
+            BinValue::I16(value) => serialize_scalar(&mut map, "i16", value)?,
+            BinValue::U16(value) => serialize_scalar(&mut map, "u16", value)?,
+            BinValue::I32(value) => serialize_scalar(&mut map, "i32", value)?,
+            BinValue::U32(value) => serialize_scalar(&mut map, "u32", value)?,
+            BinValue::I64(value) => serialize_scalar(&mut map, "i64", value)?,
+            BinValue::U64(value) => serialize_scalar(&mut map, "u64", value)?,
+            BinValue::Float(value) => serialize_scalar(&mut map, "f32", value)?,
+            BinValue::Vec2(value) => serialize_scalar(&mut map, "vec2", value)?,
+            BinValue::Vec3(value) => serialize_scalar(&mut map, "vec3", value)?,
+            BinValue::Vec4(value) => serialize_scalar(&mut map, "vec4", value)?,
+            BinValue::Mtx44(value) => serialize_scalar(&mut map, "mtx44", value)?,
+            BinValue::Rgba(value) => serialize_scalar(&mut map, "rgba", value)?,
+            BinValue::String(value) => serialize_scalar(&mut map, "string", value)?,
+            BinValue::Hash(value) => serialize_scalar(&mut map, "hash", &fnv_to_json(value))?,
+            BinValue::Link(value) => serialize_scalar(&mut map, "link", &fnv_to_json(value))?,
+            BinValue::File(value) => serialize_scalar(&mut map, "file", &xxh_to_json(value))?,
+            BinValue::List(value_type, items) | BinValue::List2(value_type, items) => {
+                map.serialize_entry("type", self.0.bin_type().name())?;
+                map.serialize_entry("value_type", value_type.name())?;
+                map.serialize_entry("value", &items.iter().map(BinJson).collect::<Vec<_>>())?;
+            }
+            BinValue::Option(value_type, value) => {
+                map.serialize_entry("type", "option")?;
+                map.serialize_entry("value_type", value_type.name())?;
+                map.serialize_entry("value", &value.as_deref().map(BinJson))?;
+            }
+            BinValue::Map(key_type, value_type, items) => {
+                map.serialize_entry("type", "map")?;
+                map.serialize_entry("key_type", key_type.name())?;
+                map.serialize_entry("value_type", value_type.name())?;
+                let items: Vec<_> = items.iter().map(|(key, value)| (BinJson(key), BinJson(value))).collect();
+                map.serialize_entry("value", &items)?;
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                map.serialize_entry("type", self.0.bin_type().name())?;
+                map.serialize_entry("name", &fnv_to_json(name))?;
+                map.serialize_entry("value", &FieldsJson(fields))?;
+            }
+            BinValue::Unknown(type_id, bytes, trailing) => {
+                map.serialize_entry("type", "unknown")?;
+                map.serialize_entry("type_id", type_id)?;
+                map.serialize_entry("trailing_fields", trailing)?;
+                map.serialize_entry("value", bytes)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl Serialize for FieldsJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in self.0 {
+            map.serialize_entry(&fnv_to_key(name), &BinJson(value))?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for PatchJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("name", &fnv_to_json(&self.0.name))?;
+        map.serialize_entry("path", &self.0.path)?;
+        map.serialize_entry("value", &BinJson(&self.0.value))?;
+        map.end()
+    }
+}
+
+impl Serialize for BinFileJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let bin = self.0;
+        let mut map = serializer.serialize_map(Some(5))?;
+        map.serialize_entry("type", if bin.is_patch { "PTCH" } else { "PROP" })?;
+        map.serialize_entry("version", &bin.version)?;
+        map.serialize_entry("links", &bin.links)?;
+        map.serialize_entry("entries", &FieldsJson(&bin.entries))?;
+        map.serialize_entry("patches", &bin.patches.iter().map(PatchJson).collect::<Vec<_>>())?;
+        map.end()
+    }
+}
+
+/// Key order of a parsed JSON document, which `Value` sorts away.
+enum JsonOrder {
+    Scalar,
+    Array(Vec<JsonOrder>),
+    Object(Vec<(String, JsonOrder)>),
+}
+
+impl JsonOrder {
+    fn get(&self, key: &str) -> Option<&JsonOrder> {
+        match self {
+            JsonOrder::Object(members) => members.iter().find(|(name, _)| name == key).map(|(_, order)| order),
+            _ => None,
+        }
+    }
+
+    fn sort_fields(&self, fields: &mut IndexMap<BinFNV, BinValue>) {
+        let members = match self {
+            JsonOrder::Object(members) => members,
+            _ => return,
+        };
+        let mut positions = HashMap::with_capacity(members.len());
+        for (index, (name, order)) in members.iter().enumerate() {
+            if let Ok(name) = fnv_from_str(name) {
+                positions.insert(name.get_hash(), index);
+                if let Some(value) = fields.get_mut(&name) {
+                    order.sort_value(value);
+                }
+            }
+        }
+        fields.sort_by_cached_key(|name, _| positions.get(&name.get_hash()).copied().unwrap_or(usize::MAX));
+    }
+
+    fn sort_value(&self, value: &mut BinValue) {
+        let order = match self.get("value") {
+            Some(order) => order,
+            None => return,
+        };
+        match (value, order) {
+            (BinValue::Struct(_, fields) | BinValue::Embed(_, fields), _) => order.sort_fields(fields),
+            (BinValue::Option(_, Some(value)), _) => order.sort_value(value),
+            (BinValue::List(_, items) | BinValue::List2(_, items), JsonOrder::Array(orders)) => {
+                for (item, order) in items.iter_mut().zip(orders) {
+                    order.sort_value(item);
+                }
+            }
+            (BinValue::Map(_, _, items), JsonOrder::Array(orders)) => {
+                for ((key, value), order) in items.iter_mut().zip(orders) {
+                    if let JsonOrder::Array(pair) = order {
+                        if let [key_order, value_order] = pair.as_slice() {
+                            key_order.sort_value(key);
+                            value_order.sort_value(value);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonOrder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonOrderVisitor)
+    }
+}
+
+struct JsonOrderVisitor;
+
+impl<'de> Visitor<'de> for JsonOrderVisitor {
+    type Value = JsonOrder;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> std::result::Result<JsonOrder, E> {
+        Ok(JsonOrder::Scalar)
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> std::result::Result<JsonOrder, E> {
+        Ok(JsonOrder::Scalar)
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> std::result::Result<JsonOrder, E> {
+        Ok(JsonOrder::Scalar)
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> std::result::Result<JsonOrder, E> {
+        Ok(JsonOrder::Scalar)
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> std::result::Result<JsonOrder, E> {
+        Ok(JsonOrder::Scalar)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<JsonOrder, E> {
+        Ok(JsonOrder::Scalar)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<JsonOrder, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(JsonOrder::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<JsonOrder, A::Error> {
+        let mut members = Vec::new();
+        while let Some(member) = map.next_entry()? {
+            members.push(member);
+        }
+        Ok(JsonOrder::Object(members))
+    }
+}
+
+impl BinValue {
+    pub fn from_json(json: &Value) -> Result<BinValue> {
+        let type_name = json["type"]
+            .as_str()
+            .ok_or_else(|| in_json_path(json_error("Missing value type"), "type"))?;
+        let value = &json["value"];
+        Ok(match type_name {
+            "none" => BinValue::None,
+            "bool" => BinValue::Bool(from_json(value).map_err(in_value)?),
+            "flag" => BinValue::Flag(from_json(value).map_err(in_value)?),
+            "unknown" => BinValue::Unknown(
+                from_json(&json["type_id"]).map_err(|e| in_json_path(e, "type_id"))?,
+                from_json(value).map_err(in_value)?,
+                match &json["trailing_fields"] {
+                    Value::Null => 0,
+                    trailing => from_json(trailing).map_err(|e| in_json_path(e, "trailing_fields"))?,
+                },
+            ),
+            "i8" => BinValue::I8(from_json(value).map_err(in_value)?),
+            "u8" => BinValue::U8(from_json(value).map_err(in_value)?),
+            "i16" => BinValue::I16(from_json(value).map_err(in_value)?),
+            "u16" => BinValue::U16(from_json(value).map_err(in_value)?),
+            "i32" => BinValue::I32(from_json(value).map_err(in_value)?),
+            "u32" => BinValue::U32(from_json(value).map_err(in_value)?),
+            "i64" => BinValue::I64(from_json(value).map_err(in_value)?),
+            "u64" => BinValue::U64(from_json(value).map_err(in_value)?),
+            "f32" => BinValue::Float(from_json(value).map_err(in_value)?),
+            "vec2" => BinValue::Vec2(from_json(value).map_err(in_value)?),
+            "vec3" => BinValue::Vec3(from_json(value).map_err(in_value)?),
+            "vec4" => BinValue::Vec4(from_json(value).map_err(in_value)?),
+            "mtx44" => BinValue::Mtx44(from_json(value).map_err(in_value)?),
+            "rgba" => BinValue::Rgba(from_json(value).map_err(in_value)?),
+            "string" => BinValue::String(from_json(value).map_err(in_value)?),
+            "hash" => BinValue::Hash(fnv_from_json(value).map_err(in_value)?),
+            "link" => BinValue::Link(fnv_from_json(value).map_err(in_value)?),
+            "file" => BinValue::File(xxh_from_json(value).map_err(in_value)?),
+            "list" | "list2" => {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| in_value(json_error("Expected list array")))?;
+                let mut result = Vec::new();
+                for (index, item) in items.iter().enumerate() {
+                    let item = BinValue::from_json(item)
+                        .map_err(|e| in_value(in_json_path(e, &index.to_string())))?;
+                    result.push(item);
+                }
+                let value_type = match type_from_json(&json["value_type"]).map_err(|e| in_json_path(e, "value_type"))? {
+                    Some(value_type) => value_type,
+                    None => BinType::of_items(result.iter()),
+                };
+                if type_name == "list" {
+                    BinValue::List(value_type, result)
+                } else {
+                    BinValue::List2(value_type, result)
+                }
+            }
+            "map" => {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| in_value(json_error("Expected map array")))?;
+                let mut result = Vec::new();
+                for (index, item) in items.iter().enumerate() {
+                    let segment = index.to_string();
+                    let pair = match item.as_array().map(|pair| pair.as_slice()) {
+                        Some([key, value]) => (
+                            BinValue::from_json(key).map_err(|e| in_json_path(e, "0")),
+                            BinValue::from_json(value).map_err(|e| in_json_path(e, "1")),
+                        ),
+                        _ => return Err(in_value(in_json_path(json_error("Expected pair"), &segment))),
+                    };
+                    match pair {
+                        (Ok(key), Ok(value)) => result.push((key, value)),
+                        (Err(e), _) | (_, Err(e)) => return Err(in_value(in_json_path(e, &segment))),
+                    }
+                }
+                let key_type = match type_from_json(&json["key_type"]).map_err(|e| in_json_path(e, "key_type"))? {
+                    Some(key_type) => key_type,
+                    None => BinType::of_items(result.iter().map(|(key, _)| key)),
+                };
+                let value_type = match type_from_json(&json["value_type"]).map_err(|e| in_json_path(e, "value_type"))? {
+                    Some(value_type) => value_type,
+                    None => BinType::of_items(result.iter().map(|(_, value)| value)),
+                };
+                BinValue::Map(key_type, value_type, result)
+            }
+            "option" => {
+                let value = match value {
+                    Value::Null => None,
+                    value => Some(Box::new(BinValue::from_json(value).map_err(in_value)?)),
+                };
+                let value_type = match type_from_json(&json["value_type"]).map_err(|e| in_json_path(e, "value_type"))? {
+                    Some(value_type) => value_type,
+                    None => BinType::of_items(value.as_deref().into_iter()),
+                };
+                BinValue::Option(value_type, value)
+            }
+            "pointer" | "embed" => {
+                let name = fnv_from_json(&json["name"]).map_err(|e| in_json_path(e, "name"))?;
+                let fields = match value {
+                    Value::Null if name.get_hash() == 0 => IndexMap::new(),
+                    _ => fields_from_json(value).map_err(in_value)?,
+                };
+                if type_name == "pointer" {
+                    BinValue::Struct(name, fields)
+                } else {
+                    BinValue::Embed(name, fields)
+                }
+            }
+            _ => {
+                let error = json_error(format!("Unknown value type {:?}", type_name));
+                return Err(in_json_path(error, "type"));
+            }
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(BinJson(self)).unwrap()
+    }
+}
+
+impl Bin {
+    pub fn read_from_json(data: &str) -> Result<Bin> {
+        let json: Value = serde_json::from_str(data).map_err(|e| json_error(e.to_string()))?;
+        let mut errors = validate_json(&json).into_iter();
+        if let Some((path, mut message)) = errors.next() {
+            let more = errors.len();
+            if more > 0 {
+                message = format!("{} (and {} more)", message, more);
+            }
+            return Err(BinError::Json { path, message });
+        }
+        let is_patch = match json["type"].as_str() {
+            Some("PROP") => false,
+            Some("PTCH") => true,
+            _ => return Err(in_json_path(json_error("Bad bin type"), "type")),
+        };
+        let version = from_json(&json["version"]).map_err(|e| in_json_path(e, "version"))?;
+        let links = from_json(&json["links"]).map_err(|e| in_json_path(e, "links"))?;
+        let mut entries = IndexMap::new();
+        let entries_json = json["entries"]
+            .as_object()
+            .ok_or_else(|| in_json_path(json_error("Expected entries object"), "entries"))?;
+        for (name, value) in entries_json {
+            let segment = format!("entries/{}", escape_pointer(name));
+            let value = BinValue::from_json(value).map_err(|e| in_json_path(e, &segment))?;
+            if !matches!(value, BinValue::Struct(_, _) | BinValue::Embed(_, _)) {
+                return Err(in_json_path(json_error("Entry must be a struct"), &segment));
+            }
+            entries.insert(fnv_from_str(name).map_err(|e| in_json_path(e, &segment))?, value);
+        }
+        let mut patches = Vec::new();
+        if let Some(patches_json) = json["patches"].as_array() {
+            for (index, patch) in patches_json.iter().enumerate() {
+                let segment = format!("patches/{}", index);
+                patches.push(BinPatch {
+                    name: fnv_from_json(&patch["name"]).map_err(|e| in_json_path(e, &format!("{}/name", segment)))?,
+                    path: from_json(&patch["path"]).map_err(|e| in_json_path(e, &format!("{}/path", segment)))?,
+                    value: BinValue::from_json(&patch["value"])
+                        .map_err(|e| in_json_path(e, &format!("{}/value", segment)))?,
+                });
+            }
+        }
+        let order: JsonOrder = serde_json::from_str(data).map_err(|e| json_error(e.to_string()))?;
+        if let Some(entries_order) = order.get("entries") {
+            entries_order.sort_fields(&mut entries);
+        }
+        if let Some(JsonOrder::Array(patches_order)) = order.get("patches") {
+            for (patch, patch_order) in patches.iter_mut().zip(patches_order) {
+                if let Some(value_order) = patch_order.get("value") {
+                    value_order.sort_value(&mut patch.value);
+                }
+            }
+        }
+        Ok(Bin {
+            is_patch,
+            version,
+            links,
+            entries,
+            patches,
+            tail: Vec::new(),
+            errors: Vec::new(),
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(BinFileJson(self)).unwrap()
+    }
+
+    pub fn write_to_json(&self) -> String {
+        serde_json::to_string_pretty(&BinFileJson(self)).unwrap()
+    }
+}
+
+impl BinDiff {
+    pub fn to_json(&self) -> Value {
+        let changed: Vec<Value> = self
+            .changed
+            .iter()
+            .map(|entry| {
+                let changes: Vec<Value> = entry
+                    .changes
+                    .iter()
+                    .map(|change| {
+                        json!({
+                            "path": change.path,
+                            "old": change.old.as_ref().map(|value| value.to_json()),
+                            "new": change.new.as_ref().map(|value| value.to_json()),
+                        })
+                    })
+                    .collect();
+                json!({ "entry": fnv_to_json(&entry.name), "changes": changes })
+            })
+            .collect();
+        json!({
+            "added": self.added.iter().map(fnv_to_json).collect::<Vec<Value>>(),
+            "removed": self.removed.iter().map(fnv_to_json).collect::<Vec<Value>>(),
+            "renamed": self
+                .renamed
+                .iter()
+                .map(|(old, new)| json!({ "old": fnv_to_json(old), "new": fnv_to_json(new) }))
+                .collect::<Vec<Value>>(),
+            "changed": changed,
+        })
+    }
+}
@@ -1,19 +1,141 @@
+#[cfg(feature = "tools")]
+mod assets;
+#[cfg(feature = "tools")]
+mod builder;
+#[cfg(feature = "tools")]
+mod checksum;
+#[cfg(feature = "tools")]
+mod collection;
+#[cfg(feature = "tools")]
+mod convert;
+#[cfg(feature = "tools")]
+mod diff;
+#[cfg(feature = "tools")]
+mod edit;
+mod error;
+#[cfg(feature = "tools")]
+mod find;
+#[cfg(feature = "tools")]
+mod fuzz;
+#[cfg(feature = "tools")]
+mod graph;
 mod hashes;
+#[cfg(feature = "tools")]
+mod infer;
+#[cfg(feature = "tools")]
+mod journal;
+#[cfg(all(feature = "serde", feature = "tools"))]
+mod json;
+#[cfg(feature = "tools")]
+mod locale;
+#[cfg(feature = "tools")]
+mod manifest;
+#[cfg(all(feature = "mmap", unix))]
+mod mmap;
+pub mod model;
+#[cfg(feature = "tools")]
+mod names;
+#[cfg(feature = "tools")]
+mod nested;
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+mod pack;
+#[cfg(feature = "tools")]
+mod pointer;
+#[cfg(feature = "tools")]
+mod project;
 mod reader;
+#[cfg(feature = "tools")]
+mod redact;
+#[cfg(feature = "remote-hashes")]
+mod remote;
+mod render;
+#[cfg(feature = "serde")]
+mod serialize;
+mod symbol;
+#[cfg(feature = "tools")]
+mod text;
+mod types;
+#[cfg(feature = "tools")]
+mod unresolved;
+#[cfg(feature = "wad")]
+mod wad;
+#[cfg(feature = "tools")]
+mod walk;
+#[cfg(feature = "tools")]
+mod words;
+mod writer;
+#[cfg(feature = "yaml")]
+mod yaml;
 
+#[cfg(feature = "tools")]
+pub use assets::*;
+#[cfg(feature = "tools")]
+pub use builder::*;
+#[cfg(feature = "tools")]
+pub use collection::*;
+#[cfg(feature = "tools")]
+pub use diff::*;
+#[cfg(feature = "tools")]
+pub use edit::BinRenameMap;
+pub use error::*;
+#[cfg(feature = "tools")]
+pub use fuzz::*;
 pub use hashes::*;
+#[cfg(feature = "tools")]
+pub use infer::*;
+#[cfg(feature = "tools")]
+pub use journal::*;
+#[cfg(all(feature = "serde", feature = "tools"))]
+pub use json::{compare_json, validate_json, BinJson};
+#[cfg(feature = "tools")]
+pub use manifest::*;
+#[cfg(feature = "tools")]
+pub use names::*;
+#[cfg(feature = "tools")]
+pub use nested::*;
+#[cfg(feature = "tools")]
+pub use project::*;
+pub use reader::BinLazyReader;
+#[cfg(feature = "tools")]
+pub use redact::redact_string;
+#[cfg(feature = "remote-hashes")]
+pub use remote::*;
+pub use render::{BinClassDisplay, BinClassRender};
+pub use symbol::Name;
+pub use types::BinType;
+#[cfg(feature = "tools")]
+pub use unresolved::*;
+#[cfg(feature = "wad")]
+pub use wad::*;
+#[cfg(feature = "tools")]
+pub use walk::*;
+#[cfg(feature = "tools")]
+pub use words::*;
+pub use writer::BinStreamWriter;
 use reader::BinReader;
+use writer::BinWriter;
+pub use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::Read;
-use std::fmt::{Debug, Display};
+use std::io::{Read, Write};
+use std::path::Path;
+#[cfg(feature = "display")]
+use std::fmt::Display;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 
 #[derive(Clone)]
 pub enum BinValue {
     None,
     Bool(bool),
-    Signed(i64),
-    Unsigned(u64),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
     Float(f32),
     Vec2([f32; 2]),
     Vec3([f32; 3]),
@@ -24,18 +146,37 @@ pub enum BinValue {
     Hash(BinFNV),
     Link(BinFNV),
     File(BinXXH),
-    List(Vec<BinValue>),
-    Map(Vec<(BinValue, BinValue)>),
-    Struct(BinFNV, HashMap<BinFNV, BinValue>),
+    List(BinType, Vec<BinValue>),
+    List2(BinType, Vec<BinValue>),
+    Option(BinType, Option<Box<BinValue>>),
+    Map(BinType, BinType, Vec<(BinValue, BinValue)>),
+    Struct(BinFNV, IndexMap<BinFNV, BinValue>),
+    Embed(BinFNV, IndexMap<BinFNV, BinValue>),
+    Flag(bool),
+    Unknown(u8, Vec<u8>, u16),
+}
+
+fn name_label(name: &BinFNV) -> String {
+    let mut label = String::new();
+    BinHashDisplay::current()
+        .write_name(&mut label, name.get_string(), name.get_hash() as u64, 8, false)
+        .unwrap();
+    label
 }
 
 impl BinValue {
     pub fn format_to(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             BinValue::None => write!(f, "None"),
-            BinValue::Bool(value) => write!(f, "{}", value),
-            BinValue::Signed(value) => write!(f, "{}", value),
-            BinValue::Unsigned(value) => write!(f, "{}", value),
+            BinValue::Bool(value) | BinValue::Flag(value) => write!(f, "{}", value),
+            BinValue::I8(value) => write!(f, "{}", value),
+            BinValue::U8(value) => write!(f, "{}", value),
+            BinValue::I16(value) => write!(f, "{}", value),
+            BinValue::U16(value) => write!(f, "{}", value),
+            BinValue::I32(value) => write!(f, "{}", value),
+            BinValue::U32(value) => write!(f, "{}", value),
+            BinValue::I64(value) => write!(f, "{}", value),
+            BinValue::U64(value) => write!(f, "{}", value),
             BinValue::Float(value) => write!(f, "{}", value),
             BinValue::Vec2(value) => write!(f, "{:?}", value),
             BinValue::Vec3(value) => write!(f, "{:?}", value),
@@ -46,8 +187,10 @@ impl BinValue {
             BinValue::Hash(value) => value.format_to(f),
             BinValue::Link(value) => value.format_to(f),
             BinValue::File(value) => value.format_to(f),
-            BinValue::List(value) =>  write!(f, "{:#?}", value),
-            BinValue::Map(value) => {
+            BinValue::List(_, value) | BinValue::List2(_, value) => f.debug_list().entries(value).finish(),
+            BinValue::Option(_, Some(value)) => value.format_to(f),
+            BinValue::Option(_, None) => write!(f, "None"),
+            BinValue::Map(_, _, value) => {
                 let mut debug = f.debug_map();
                 for (key, value) in value {
                     debug.key(key);
@@ -55,27 +198,31 @@ impl BinValue {
                 }
                 debug.finish()
             },
-            BinValue::Struct(name, fields) => {
-                let name = if name.get_string().len() != 0 {
-                    name.get_string().to_string()
-                } else {
-                    format!("0x{:08X}", name.get_hash())
-                };
-                let mut debug = f.debug_struct(&name);
+            BinValue::Struct(_, _) | BinValue::Embed(_, _) if self.is_null() => write!(f, "None"),
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                let display = BinClassDisplay::get(name.get_hash());
+                if let Some(render) = display.as_deref().and_then(|display| display.render) {
+                    return render(name, fields, f);
+                }
+                let label;
+                let mut debug = f.debug_struct(match display.as_deref().and_then(|display| display.name.as_deref()) {
+                    Some(name) => name,
+                    None => {
+                        label = name_label(name);
+                        &label
+                    }
+                });
                 for (name, value) in fields {
-                    let name = if name.get_string().len() != 0 {
-                        name.get_string().to_string()
-                    } else {
-                        format!("0x{:08X}", name.get_hash())
-                    };
-                    debug.field(&name, value);
+                    debug.field(&name_label(name), value);
                 }
                 debug.finish()
             }
+            BinValue::Unknown(type_id, bytes, _) => write!(f, "Unknown(0x{:02X}, {} bytes)", type_id, bytes.len()),
         }
     }
 }
 
+#[cfg(feature = "display")]
 impl Display for BinValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.format_to(f)
@@ -88,23 +235,207 @@ impl Debug for BinValue {
     }
 }
 
+fn float_bits(values: &[f32]) -> impl Iterator<Item = u32> + '_ {
+    values.iter().map(|value| value.to_bits())
+}
+
+// Floats compare by bit pattern: NaN equals an identical NaN and 0.0 differs from -0.0,
+// which keeps equality reflexive and consistent with Hash.
+impl PartialEq for BinValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BinValue::None, BinValue::None) => true,
+            (BinValue::Bool(left), BinValue::Bool(right)) => left == right,
+            (BinValue::I8(left), BinValue::I8(right)) => left == right,
+            (BinValue::U8(left), BinValue::U8(right)) => left == right,
+            (BinValue::I16(left), BinValue::I16(right)) => left == right,
+            (BinValue::U16(left), BinValue::U16(right)) => left == right,
+            (BinValue::I32(left), BinValue::I32(right)) => left == right,
+            (BinValue::U32(left), BinValue::U32(right)) => left == right,
+            (BinValue::I64(left), BinValue::I64(right)) => left == right,
+            (BinValue::U64(left), BinValue::U64(right)) => left == right,
+            (BinValue::Float(left), BinValue::Float(right)) => left.to_bits() == right.to_bits(),
+            (BinValue::Vec2(left), BinValue::Vec2(right)) => float_bits(left).eq(float_bits(right)),
+            (BinValue::Vec3(left), BinValue::Vec3(right)) => float_bits(left).eq(float_bits(right)),
+            (BinValue::Vec4(left), BinValue::Vec4(right)) => float_bits(left).eq(float_bits(right)),
+            (BinValue::Mtx44(left), BinValue::Mtx44(right)) => float_bits(left.as_flattened()).eq(float_bits(right.as_flattened())),
+            (BinValue::Rgba(left), BinValue::Rgba(right)) => left == right,
+            (BinValue::String(left), BinValue::String(right)) => left == right,
+            (BinValue::Hash(left), BinValue::Hash(right)) => left == right,
+            (BinValue::Link(left), BinValue::Link(right)) => left == right,
+            (BinValue::File(left), BinValue::File(right)) => left == right,
+            (BinValue::List(left, left_items), BinValue::List(right, right_items)) => {
+                left == right && left_items == right_items
+            }
+            (BinValue::List2(left, left_items), BinValue::List2(right, right_items)) => {
+                left == right && left_items == right_items
+            }
+            (BinValue::Option(left, left_value), BinValue::Option(right, right_value)) => {
+                left == right && left_value == right_value
+            }
+            (BinValue::Map(left_key, left_value, left_items), BinValue::Map(right_key, right_value, right_items)) => {
+                left_key == right_key && left_value == right_value && left_items == right_items
+            }
+            (BinValue::Struct(left, left_fields), BinValue::Struct(right, right_fields)) => {
+                left == right && left_fields == right_fields
+            }
+            (BinValue::Embed(left, left_fields), BinValue::Embed(right, right_fields)) => {
+                left == right && left_fields == right_fields
+            }
+            (BinValue::Flag(left), BinValue::Flag(right)) => left == right,
+            (BinValue::Unknown(left, left_bytes, left_trailing), BinValue::Unknown(right, right_bytes, right_trailing)) => {
+                left == right && left_bytes == right_bytes && left_trailing == right_trailing
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for BinValue {}
+
+// Struct fields compare regardless of order, so they are hashed in hash order.
+impl Hash for BinValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            BinValue::None => {}
+            BinValue::Bool(value) | BinValue::Flag(value) => value.hash(state),
+            BinValue::I8(value) => value.hash(state),
+            BinValue::U8(value) => value.hash(state),
+            BinValue::I16(value) => value.hash(state),
+            BinValue::U16(value) => value.hash(state),
+            BinValue::I32(value) => value.hash(state),
+            BinValue::U32(value) => value.hash(state),
+            BinValue::I64(value) => value.hash(state),
+            BinValue::U64(value) => value.hash(state),
+            BinValue::Float(value) => value.to_bits().hash(state),
+            BinValue::Vec2(value) => float_bits(value).for_each(|bits| bits.hash(state)),
+            BinValue::Vec3(value) => float_bits(value).for_each(|bits| bits.hash(state)),
+            BinValue::Vec4(value) => float_bits(value).for_each(|bits| bits.hash(state)),
+            BinValue::Mtx44(value) => float_bits(value.as_flattened()).for_each(|bits| bits.hash(state)),
+            BinValue::Rgba(value) => value.hash(state),
+            BinValue::String(value) => value.hash(state),
+            BinValue::Hash(value) | BinValue::Link(value) => value.hash(state),
+            BinValue::File(value) => value.hash(state),
+            BinValue::List(value_type, items) | BinValue::List2(value_type, items) => {
+                value_type.hash(state);
+                items.hash(state);
+            }
+            BinValue::Option(value_type, value) => {
+                value_type.hash(state);
+                value.hash(state);
+            }
+            BinValue::Map(key_type, value_type, items) => {
+                key_type.hash(state);
+                value_type.hash(state);
+                items.hash(state);
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                name.hash(state);
+                let mut fields: Vec<(&BinFNV, &BinValue)> = fields.iter().collect();
+                fields.sort_by_key(|(name, _)| name.get_hash());
+                fields.hash(state);
+            }
+            BinValue::Unknown(type_id, bytes, trailing) => {
+                type_id.hash(state);
+                bytes.hash(state);
+                trailing.hash(state);
+            }
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinFormat {
+    Auto,
+    Modern,
+    Legacy,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReadOptions {
+    pub max_string_len: usize,
+    pub max_list_len: usize,
+    pub max_map_len: usize,
+    pub max_fields: usize,
+    pub max_entries: usize,
+    pub recover_unknown: bool,
+    pub strict: bool,
+    pub format: BinFormat,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            max_string_len: usize::MAX,
+            max_list_len: usize::MAX,
+            max_map_len: usize::MAX,
+            max_fields: usize::MAX,
+            max_entries: usize::MAX,
+            recover_unknown: false,
+            strict: true,
+            format: BinFormat::Auto,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinPatch {
+    pub name: BinFNV,
+    pub path: String,
+    pub value: BinValue,
+}
 
 #[derive(Clone, Debug)]
 pub struct Bin {
+    pub is_patch: bool,
     pub version: u32,
     pub links: Vec<String>,
-    pub entries: HashMap<BinFNV, BinValue>,
+    pub entries: IndexMap<BinFNV, BinValue>,
+    pub patches: Vec<BinPatch>,
+    pub tail: Vec<u8>,
+    pub errors: Vec<(BinFNV, BinError)>,
 }
 
 impl Bin {
-    pub fn read_from_data(data: &[u8], hashes: &BinHashes) -> std::io::Result<Bin> {
-        BinReader::read_bin(data, hashes)
+    pub fn read_from_data(data: &[u8], hashes: &BinHashes) -> Result<Bin, BinError> {
+        Self::read_from_data_with_options(data, hashes, &ReadOptions::default())
+    }
+
+    pub fn read_from_data_with_options(
+        data: &[u8],
+        hashes: &BinHashes,
+        options: &ReadOptions,
+    ) -> Result<Bin, BinError> {
+        BinReader::read_bin(data, hashes, options)
     }
 
-    pub fn read_from_file(file: File, hashes: &BinHashes) -> std::io::Result<Bin> {
+    pub fn read_from_file(file: File, hashes: &BinHashes) -> Result<Bin, BinError> {
         let mut file = file;
         let mut buf = Vec::new();
         file.read_to_end(&mut buf)?;
         Self::read_from_data(buf.as_slice(), hashes)
     }
+
+    #[cfg(all(feature = "mmap", unix))]
+    pub fn read_from_path<P: AsRef<Path>>(path: P, hashes: &BinHashes) -> Result<Bin, BinError> {
+        let map = mmap::Mmap::open(&File::open(path)?)?;
+        Self::read_from_data(map.as_slice(), hashes)
+    }
+
+    #[cfg(not(all(feature = "mmap", unix)))]
+    pub fn read_from_path<P: AsRef<Path>>(path: P, hashes: &BinHashes) -> Result<Bin, BinError> {
+        Self::read_from_data(&std::fs::read(path)?, hashes)
+    }
+
+    pub fn write_to_data(&self) -> Result<Vec<u8>, BinError> {
+        BinWriter::write_bin(self)
+    }
+
+    pub fn write_to_file(&self, file: File) -> Result<(), BinError> {
+        let mut file = file;
+        file.write_all(&self.write_to_data()?)?;
+        Ok(())
+    }
 }
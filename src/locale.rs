@@ -0,0 +1,66 @@
+use crate::*;
+use std::collections::{BTreeMap, HashSet};
+
+fn collect_hashes(value: &BinValue, keys: &mut BTreeMap<u32, BinFNV>) {
+    match value {
+        BinValue::Hash(hash) => {
+            keys.entry(hash.get_hash()).or_insert_with(|| *hash);
+        }
+        BinValue::List(_, items) | BinValue::List2(_, items) => {
+            for item in items {
+                collect_hashes(item, keys);
+            }
+        }
+        BinValue::Option(_, Some(item)) => collect_hashes(item, keys),
+        BinValue::Map(_, _, items) => {
+            for (key, value) in items {
+                collect_hashes(key, keys);
+                collect_hashes(value, keys);
+            }
+        }
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+            for value in fields.values() {
+                collect_hashes(value, keys);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_locale_keys(value: &BinValue, fields: &HashSet<u32>, keys: &mut BTreeMap<u32, BinFNV>) {
+    match value {
+        BinValue::List(_, items) | BinValue::List2(_, items) => {
+            for item in items {
+                collect_locale_keys(item, fields, keys);
+            }
+        }
+        BinValue::Option(_, Some(item)) => collect_locale_keys(item, fields, keys),
+        BinValue::Map(_, _, items) => {
+            for (key, value) in items {
+                collect_locale_keys(key, fields, keys);
+                collect_locale_keys(value, fields, keys);
+            }
+        }
+        BinValue::Struct(_, values) | BinValue::Embed(_, values) => {
+            for (name, value) in values {
+                if fields.contains(&name.get_hash()) {
+                    collect_hashes(value, keys);
+                } else {
+                    collect_locale_keys(value, fields, keys);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Bin {
+    pub fn locale_keys(&self, fields: &[&str]) -> Vec<BinFNV> {
+        let fields: HashSet<u32> = fields.iter().map(|field| fnv(field)).collect();
+        let mut keys = BTreeMap::new();
+        for value in self.entries.values() {
+            collect_locale_keys(value, &fields, &mut keys);
+        }
+        keys.into_values().collect()
+    }
+}
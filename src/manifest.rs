@@ -0,0 +1,66 @@
+use crate::*;
+use std::collections::BTreeSet;
+
+#[derive(Clone, Debug, Default)]
+pub struct BinManifest {
+    pub entries: Vec<BinFNV>,
+    pub files: Vec<String>,
+}
+
+fn is_asset_path(string: &str) -> bool {
+    match string.rsplit_once('.') {
+        Some((stem, extension)) => {
+            stem.contains('/')
+                && (2..=4).contains(&extension.len())
+                && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+        None => false,
+    }
+}
+
+fn collect_files(value: &BinValue, files: &mut BTreeSet<String>) {
+    match value {
+        BinValue::File(file) => {
+            if file.get_string().is_empty() {
+                files.insert(format!("0x{:016X}", file.get_hash()));
+            } else {
+                files.insert(file.get_string().to_string());
+            }
+        }
+        BinValue::String(string) if is_asset_path(string) => {
+            files.insert(string.clone());
+        }
+        BinValue::List(_, items) | BinValue::List2(_, items) => {
+            for item in items {
+                collect_files(item, files);
+            }
+        }
+        BinValue::Option(_, Some(item)) => collect_files(item, files),
+        BinValue::Map(_, _, items) => {
+            for (key, value) in items {
+                collect_files(key, files);
+                collect_files(value, files);
+            }
+        }
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+            for value in fields.values() {
+                collect_files(value, files);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Bin {
+    pub fn asset_manifest(&self, root: &BinFNV) -> BinManifest {
+        let entries = self.closure(root, None);
+        let mut files = BTreeSet::new();
+        for name in &entries {
+            collect_files(&self.entries[name], &mut files);
+        }
+        BinManifest {
+            entries,
+            files: files.into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{BinError, BinFNV, BinHashed, BinHashes, BinType, BinXXH, BinValue};
+use indexmap::IndexMap;
+use std::convert::TryFrom;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Name {
+    pub hash: u32,
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Path {
+    pub hash: u64,
+    pub path: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Struct {
+    pub class: Name,
+    pub fields: Vec<(Name, Value)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Value {
+    None,
+    Bool(bool),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    Mtx44([[f32; 4]; 4]),
+    Rgba([u8; 4]),
+    String(String),
+    Hash(Name),
+    Link(Name),
+    File(Path),
+    List(Vec<Value>),
+    List2(Vec<Value>),
+    Option { value_type: u8, value: Option<Box<Value>> },
+    Map(Vec<(Value, Value)>),
+    Pointer(Struct),
+    Embed(Struct),
+    Flag(bool),
+    Unknown { type_id: u8, bytes: Vec<u8>, trailing_fields: u16 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Patch {
+    pub entry: Name,
+    pub path: String,
+    pub value: Value,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bin {
+    pub is_patch: bool,
+    pub version: u32,
+    pub links: Vec<String>,
+    pub entries: Vec<(Name, Value)>,
+    pub patches: Vec<Patch>,
+    pub tail: Vec<u8>,
+}
+
+impl Bin {
+    pub fn read_from_data(data: &[u8], hashes: &BinHashes) -> Result<Bin, BinError> {
+        crate::Bin::read_from_data(data, hashes).map(|bin| Bin::from(&bin))
+    }
+
+    pub fn write_to_data(&self) -> Result<Vec<u8>, BinError> {
+        crate::Bin::from(self).write_to_data()
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&Value> {
+        let hash = crate::fnv(name);
+        self.entries.iter().find(|(name, _)| name.hash == hash).map(|(_, value)| value)
+    }
+}
+
+impl From<&BinFNV> for Name {
+    fn from(name: &BinFNV) -> Self {
+        Name {
+            hash: name.get_hash(),
+            name: Some(name.get_string()).filter(|name| !name.is_empty()).map(str::to_string),
+        }
+    }
+}
+
+impl From<&Name> for BinFNV {
+    fn from(name: &Name) -> Self {
+        match &name.name {
+            Some(string) => BinFNV::from_hash_string(name.hash, string),
+            None => BinFNV::from_hash(name.hash),
+        }
+    }
+}
+
+impl From<&BinXXH> for Path {
+    fn from(path: &BinXXH) -> Self {
+        Path {
+            hash: path.get_hash(),
+            path: Some(path.get_string()).filter(|path| !path.is_empty()).map(str::to_string),
+        }
+    }
+}
+
+impl From<&Path> for BinXXH {
+    fn from(path: &Path) -> Self {
+        match &path.path {
+            Some(string) => BinXXH::from_hash_string(path.hash, string),
+            None => BinXXH::from_hash(path.hash),
+        }
+    }
+}
+
+fn model_fields(fields: &IndexMap<BinFNV, BinValue>) -> Vec<(Name, Value)> {
+    fields
+        .iter()
+        .map(|(name, value)| (Name::from(name), Value::from(value)))
+        .collect()
+}
+
+fn bin_fields(fields: &[(Name, Value)]) -> IndexMap<BinFNV, BinValue> {
+    fields
+        .iter()
+        .map(|(name, value)| (BinFNV::from(name), BinValue::from(value)))
+        .collect()
+}
+
+impl From<&BinValue> for Value {
+    fn from(value: &BinValue) -> Self {
+        match value {
+            BinValue::None => Value::None,
+            BinValue::Bool(value) => Value::Bool(*value),
+            BinValue::I8(value) => Value::I8(*value),
+            BinValue::U8(value) => Value::U8(*value),
+            BinValue::I16(value) => Value::I16(*value),
+            BinValue::U16(value) => Value::U16(*value),
+            BinValue::I32(value) => Value::I32(*value),
+            BinValue::U32(value) => Value::U32(*value),
+            BinValue::I64(value) => Value::I64(*value),
+            BinValue::U64(value) => Value::U64(*value),
+            BinValue::Float(value) => Value::F32(*value),
+            BinValue::Vec2(value) => Value::Vec2(*value),
+            BinValue::Vec3(value) => Value::Vec3(*value),
+            BinValue::Vec4(value) => Value::Vec4(*value),
+            BinValue::Mtx44(value) => Value::Mtx44(*value),
+            BinValue::Rgba(value) => Value::Rgba(*value),
+            BinValue::String(value) => Value::String(value.clone()),
+            BinValue::Hash(value) => Value::Hash(Name::from(value)),
+            BinValue::Link(value) => Value::Link(Name::from(value)),
+            BinValue::File(value) => Value::File(Path::from(value)),
+            BinValue::List(_, items) => Value::List(items.iter().map(Value::from).collect()),
+            BinValue::List2(_, items) => Value::List2(items.iter().map(Value::from).collect()),
+            BinValue::Option(value_type, value) => Value::Option {
+                value_type: *value_type as u8,
+                value: value.as_deref().map(|value| Box::new(Value::from(value))),
+            },
+            BinValue::Map(_, _, items) => Value::Map(
+                items
+                    .iter()
+                    .map(|(key, value)| (Value::from(key), Value::from(value)))
+                    .collect(),
+            ),
+            BinValue::Struct(class, fields) => Value::Pointer(Struct {
+                class: Name::from(class),
+                fields: model_fields(fields),
+            }),
+            BinValue::Embed(class, fields) => Value::Embed(Struct {
+                class: Name::from(class),
+                fields: model_fields(fields),
+            }),
+            BinValue::Flag(value) => Value::Flag(*value),
+            BinValue::Unknown(type_id, bytes, trailing_fields) => Value::Unknown {
+                type_id: *type_id,
+                bytes: bytes.clone(),
+                trailing_fields: *trailing_fields,
+            },
+        }
+    }
+}
+
+impl From<&Value> for BinValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::None => BinValue::None,
+            Value::Bool(value) => BinValue::Bool(*value),
+            Value::I8(value) => BinValue::I8(*value),
+            Value::U8(value) => BinValue::U8(*value),
+            Value::I16(value) => BinValue::I16(*value),
+            Value::U16(value) => BinValue::U16(*value),
+            Value::I32(value) => BinValue::I32(*value),
+            Value::U32(value) => BinValue::U32(*value),
+            Value::I64(value) => BinValue::I64(*value),
+            Value::U64(value) => BinValue::U64(*value),
+            Value::F32(value) => BinValue::Float(*value),
+            Value::Vec2(value) => BinValue::Vec2(*value),
+            Value::Vec3(value) => BinValue::Vec3(*value),
+            Value::Vec4(value) => BinValue::Vec4(*value),
+            Value::Mtx44(value) => BinValue::Mtx44(*value),
+            Value::Rgba(value) => BinValue::Rgba(*value),
+            Value::String(value) => BinValue::String(value.clone()),
+            Value::Hash(value) => BinValue::Hash(BinFNV::from(value)),
+            Value::Link(value) => BinValue::Link(BinFNV::from(value)),
+            Value::File(value) => BinValue::File(BinXXH::from(value)),
+            Value::List(items) => {
+                let items: Vec<BinValue> = items.iter().map(BinValue::from).collect();
+                BinValue::List(BinType::of_items(items.iter()), items)
+            }
+            Value::List2(items) => {
+                let items: Vec<BinValue> = items.iter().map(BinValue::from).collect();
+                BinValue::List2(BinType::of_items(items.iter()), items)
+            }
+            Value::Option { value_type, value } => BinValue::Option(
+                BinType::try_from(*value_type).unwrap_or(BinType::None),
+                value.as_deref().map(|value| Box::new(BinValue::from(value))),
+            ),
+            Value::Map(items) => {
+                let items: Vec<(BinValue, BinValue)> = items
+                    .iter()
+                    .map(|(key, value)| (BinValue::from(key), BinValue::from(value)))
+                    .collect();
+                let key_type = BinType::of_items(items.iter().map(|(key, _)| key));
+                let value_type = BinType::of_items(items.iter().map(|(_, value)| value));
+                BinValue::Map(key_type, value_type, items)
+            }
+            Value::Pointer(value) => BinValue::Struct(BinFNV::from(&value.class), bin_fields(&value.fields)),
+            Value::Embed(value) => BinValue::Embed(BinFNV::from(&value.class), bin_fields(&value.fields)),
+            Value::Flag(value) => BinValue::Flag(*value),
+            Value::Unknown {
+                type_id,
+                bytes,
+                trailing_fields,
+            } => BinValue::Unknown(*type_id, bytes.clone(), *trailing_fields),
+        }
+    }
+}
+
+impl From<&crate::BinPatch> for Patch {
+    fn from(patch: &crate::BinPatch) -> Self {
+        Patch {
+            entry: Name::from(&patch.name),
+            path: patch.path.clone(),
+            value: Value::from(&patch.value),
+        }
+    }
+}
+
+impl From<&Patch> for crate::BinPatch {
+    fn from(patch: &Patch) -> Self {
+        crate::BinPatch {
+            name: BinFNV::from(&patch.entry),
+            path: patch.path.clone(),
+            value: BinValue::from(&patch.value),
+        }
+    }
+}
+
+impl From<&crate::Bin> for Bin {
+    fn from(bin: &crate::Bin) -> Self {
+        Bin {
+            is_patch: bin.is_patch,
+            version: bin.version,
+            links: bin.links.clone(),
+            entries: model_fields(&bin.entries),
+            patches: bin.patches.iter().map(Patch::from).collect(),
+            tail: bin.tail.clone(),
+        }
+    }
+}
+
+impl From<&Bin> for crate::Bin {
+    fn from(bin: &Bin) -> Self {
+        crate::Bin {
+            is_patch: bin.is_patch,
+            version: bin.version,
+            links: bin.links.clone(),
+            entries: bin_fields(&bin.entries),
+            patches: bin.patches.iter().map(crate::BinPatch::from).collect(),
+            tail: bin.tail.clone(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BinValue::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        BinValue::deserialize(deserializer).map(|value| Value::from(&value))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Bin {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::Bin::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Bin {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::Bin::deserialize(deserializer).map(|bin| Bin::from(&bin))
+    }
+}
@@ -0,0 +1,32 @@
+use crate::*;
+
+pub fn split_entry_path(name: &str) -> Vec<&str> {
+    name.split('/').filter(|segment| !segment.is_empty()).collect()
+}
+
+pub fn join_entry_path<S: AsRef<str>>(segments: &[S]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.as_ref())
+        .collect::<Vec<&str>>()
+        .join("/")
+}
+
+pub fn entry_path_has_prefix(name: &str, prefix: &str) -> bool {
+    let segments = split_entry_path(name);
+    let prefix = split_entry_path(prefix);
+    segments.len() >= prefix.len()
+        && segments
+            .iter()
+            .zip(prefix.iter())
+            .all(|(a, b)| a.eq_ignore_ascii_case(b))
+}
+
+impl Bin {
+    pub fn entries_with_prefix(&self, prefix: &str) -> Vec<(&BinFNV, &BinValue)> {
+        self.entries
+            .iter()
+            .filter(|(name, _)| entry_path_has_prefix(name.get_string(), prefix))
+            .collect()
+    }
+}
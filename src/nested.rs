@@ -0,0 +1,95 @@
+use crate::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+pub trait BinContentProvider {
+    fn read_content(&self, path: &str) -> Option<Vec<u8>>;
+}
+
+impl BinContentProvider for PathBuf {
+    fn read_content(&self, path: &str) -> Option<Vec<u8>> {
+        fs::read(self.join(path))
+            .or_else(|_| fs::read(self.join(path.to_ascii_lowercase())))
+            .ok()
+    }
+}
+
+#[cfg(feature = "wad")]
+impl BinContentProvider for BinWad {
+    fn read_content(&self, path: &str) -> Option<Vec<u8>> {
+        let hash = xxh64(path);
+        let chunk = self.chunks.iter().find(|chunk| chunk.path.get_hash() == hash)?;
+        self.chunk_data(chunk).ok()
+    }
+}
+
+fn bin_reference(value: &BinValue) -> Option<&str> {
+    let path = match value {
+        BinValue::String(string) => string.as_str(),
+        BinValue::File(file) => file.get_string(),
+        _ => return None,
+    };
+    Some(path).filter(|path| path.to_ascii_lowercase().ends_with(".bin"))
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").to_ascii_lowercase()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BinNested {
+    pub bins: BTreeMap<String, Bin>,
+    pub missing: Vec<String>,
+}
+
+impl BinNested {
+    pub fn get(&self, path: &str) -> Option<&Bin> {
+        self.bins.get(&normalize_path(path))
+    }
+
+    pub fn select<'a>(&'a self, bin: &'a Bin, query: &str) -> Option<&'a BinValue> {
+        let mut parts = query.split("->");
+        let mut value = bin.select(parts.next()?)?;
+        for part in parts {
+            value = self.get(bin_reference(value)?)?.select(part)?;
+        }
+        Some(value)
+    }
+}
+
+impl Bin {
+    pub fn referenced_bins(&self) -> Vec<String> {
+        let mut result = BTreeSet::new();
+        self.walk(|_, value| {
+            if let Some(path) = bin_reference(value) {
+                result.insert(normalize_path(path));
+            }
+        });
+        result.into_iter().collect()
+    }
+
+    pub fn resolve_nested(&self, provider: &dyn BinContentProvider, hashes: &BinHashes, depth: usize) -> BinNested {
+        let mut result = BinNested::default();
+        let mut pending: Vec<(String, usize)> = self.referenced_bins().into_iter().map(|path| (path, 1)).collect();
+        while let Some((path, level)) = pending.pop() {
+            if result.bins.contains_key(&path) || result.missing.contains(&path) {
+                continue;
+            }
+            let bin = provider
+                .read_content(&path)
+                .and_then(|data| Bin::read_from_data(&data, hashes).ok());
+            match bin {
+                Some(bin) => {
+                    if level < depth {
+                        pending.extend(bin.referenced_bins().into_iter().map(|path| (path, level + 1)));
+                    }
+                    result.bins.insert(path, bin);
+                }
+                None => result.missing.push(path),
+            }
+        }
+        result.missing.sort();
+        result
+    }
+}
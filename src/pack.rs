@@ -0,0 +1,305 @@
+use crate::*;
+
+trait Packer {
+    fn pack_nil(&mut self);
+    fn pack_bool(&mut self, value: bool);
+    fn pack_uint(&mut self, value: u64);
+    fn pack_int(&mut self, value: i64);
+    fn pack_f32(&mut self, value: f32);
+    fn pack_str(&mut self, value: &str);
+    fn pack_bytes(&mut self, value: &[u8]);
+    fn pack_array(&mut self, len: usize);
+    fn pack_map(&mut self, len: usize);
+
+    fn pack_floats(&mut self, values: &[f32]) {
+        self.pack_array(values.len());
+        for value in values {
+            self.pack_f32(*value);
+        }
+    }
+
+    fn pack_name(&mut self, name: &BinFNV) {
+        if name.get_string().is_empty() {
+            self.pack_uint(name.get_hash() as u64);
+        } else {
+            self.pack_array(2);
+            self.pack_uint(name.get_hash() as u64);
+            self.pack_str(name.get_string());
+        }
+    }
+
+    fn pack_path(&mut self, path: &BinXXH) {
+        if path.get_string().is_empty() {
+            self.pack_uint(path.get_hash());
+        } else {
+            self.pack_array(2);
+            self.pack_uint(path.get_hash());
+            self.pack_str(path.get_string());
+        }
+    }
+
+    fn pack_fields(&mut self, fields: &IndexMap<BinFNV, BinValue>) {
+        self.pack_map(fields.len());
+        for (name, value) in fields {
+            self.pack_name(name);
+            self.pack_value(value);
+        }
+    }
+
+    fn pack_value(&mut self, value: &BinValue) {
+        self.pack_array(2);
+        match value {
+            BinValue::Unknown(type_id, _, _) => self.pack_uint(*type_id as u64),
+            _ => self.pack_uint(value.bin_type() as u64),
+        }
+        match value {
+            BinValue::None => self.pack_nil(),
+            BinValue::Bool(value) | BinValue::Flag(value) => self.pack_bool(*value),
+            BinValue::I8(value) => self.pack_int(*value as i64),
+            BinValue::U8(value) => self.pack_uint(*value as u64),
+            BinValue::I16(value) => self.pack_int(*value as i64),
+            BinValue::U16(value) => self.pack_uint(*value as u64),
+            BinValue::I32(value) => self.pack_int(*value as i64),
+            BinValue::U32(value) => self.pack_uint(*value as u64),
+            BinValue::I64(value) => self.pack_int(*value),
+            BinValue::U64(value) => self.pack_uint(*value),
+            BinValue::Float(value) => self.pack_f32(*value),
+            BinValue::Vec2(value) => self.pack_floats(value),
+            BinValue::Vec3(value) => self.pack_floats(value),
+            BinValue::Vec4(value) => self.pack_floats(value),
+            BinValue::Mtx44(value) => {
+                self.pack_array(4);
+                for row in value {
+                    self.pack_floats(row);
+                }
+            }
+            BinValue::Rgba(value) => self.pack_bytes(value),
+            BinValue::String(value) => self.pack_str(value),
+            BinValue::Hash(name) | BinValue::Link(name) => self.pack_name(name),
+            BinValue::File(path) => self.pack_path(path),
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                self.pack_array(items.len());
+                for item in items {
+                    self.pack_value(item);
+                }
+            }
+            BinValue::Option(_, Some(value)) => self.pack_value(value),
+            BinValue::Option(_, None) => self.pack_nil(),
+            BinValue::Map(_, _, items) => {
+                self.pack_map(items.len());
+                for (key, value) in items {
+                    self.pack_value(key);
+                    self.pack_value(value);
+                }
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                self.pack_array(2);
+                self.pack_name(name);
+                self.pack_fields(fields);
+            }
+            BinValue::Unknown(_, bytes, _) => self.pack_bytes(bytes),
+        }
+    }
+
+    fn pack_bin(&mut self, bin: &Bin) {
+        self.pack_map(6);
+        self.pack_str("type");
+        self.pack_str(if bin.is_patch { "PTCH" } else { "PROP" });
+        self.pack_str("version");
+        self.pack_uint(bin.version as u64);
+        self.pack_str("linked");
+        self.pack_array(bin.links.len());
+        for link in &bin.links {
+            self.pack_str(link);
+        }
+        self.pack_str("entries");
+        self.pack_fields(&bin.entries);
+        self.pack_str("patches");
+        self.pack_array(bin.patches.len());
+        for patch in &bin.patches {
+            self.pack_array(3);
+            self.pack_name(&patch.name);
+            self.pack_str(&patch.path);
+            self.pack_value(&patch.value);
+        }
+        self.pack_str("tail");
+        self.pack_bytes(&bin.tail);
+    }
+}
+
+#[cfg(feature = "msgpack")]
+struct MsgPacker(Vec<u8>);
+
+#[cfg(feature = "msgpack")]
+impl MsgPacker {
+    fn pack_len(&mut self, len: usize, fix: Option<(u8, usize)>, marker8: Option<u8>, marker16: u8, marker32: u8) {
+        match (fix, marker8) {
+            (Some((fix, max)), _) if len < max => self.0.push(fix | len as u8),
+            (_, Some(marker8)) if len <= u8::MAX as usize => self.0.extend_from_slice(&[marker8, len as u8]),
+            _ if len <= u16::MAX as usize => {
+                self.0.push(marker16);
+                self.0.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            _ => {
+                self.0.push(marker32);
+                self.0.extend_from_slice(&(len as u32).to_be_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl Packer for MsgPacker {
+    fn pack_nil(&mut self) {
+        self.0.push(0xC0);
+    }
+
+    fn pack_bool(&mut self, value: bool) {
+        self.0.push(if value { 0xC3 } else { 0xC2 });
+    }
+
+    fn pack_uint(&mut self, value: u64) {
+        if value < 0x80 {
+            self.0.push(value as u8);
+        } else if value <= u8::MAX as u64 {
+            self.0.extend_from_slice(&[0xCC, value as u8]);
+        } else if value <= u16::MAX as u64 {
+            self.0.push(0xCD);
+            self.0.extend_from_slice(&(value as u16).to_be_bytes());
+        } else if value <= u32::MAX as u64 {
+            self.0.push(0xCE);
+            self.0.extend_from_slice(&(value as u32).to_be_bytes());
+        } else {
+            self.0.push(0xCF);
+            self.0.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    fn pack_int(&mut self, value: i64) {
+        if value >= 0 {
+            self.pack_uint(value as u64);
+        } else if value >= -32 {
+            self.0.push(value as u8);
+        } else if value >= i8::MIN as i64 {
+            self.0.extend_from_slice(&[0xD0, value as u8]);
+        } else if value >= i16::MIN as i64 {
+            self.0.push(0xD1);
+            self.0.extend_from_slice(&(value as i16).to_be_bytes());
+        } else if value >= i32::MIN as i64 {
+            self.0.push(0xD2);
+            self.0.extend_from_slice(&(value as i32).to_be_bytes());
+        } else {
+            self.0.push(0xD3);
+            self.0.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    fn pack_f32(&mut self, value: f32) {
+        self.0.push(0xCA);
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn pack_str(&mut self, value: &str) {
+        self.pack_len(value.len(), Some((0xA0, 32)), Some(0xD9), 0xDA, 0xDB);
+        self.0.extend_from_slice(value.as_bytes());
+    }
+
+    fn pack_bytes(&mut self, value: &[u8]) {
+        self.pack_len(value.len(), None, Some(0xC4), 0xC5, 0xC6);
+        self.0.extend_from_slice(value);
+    }
+
+    fn pack_array(&mut self, len: usize) {
+        self.pack_len(len, Some((0x90, 16)), None, 0xDC, 0xDD);
+    }
+
+    fn pack_map(&mut self, len: usize) {
+        self.pack_len(len, Some((0x80, 16)), None, 0xDE, 0xDF);
+    }
+}
+
+#[cfg(feature = "cbor")]
+struct CborPacker(Vec<u8>);
+
+#[cfg(feature = "cbor")]
+impl CborPacker {
+    fn pack_head(&mut self, major: u8, value: u64) {
+        let major = major << 5;
+        if value < 24 {
+            self.0.push(major | value as u8);
+        } else if value <= u8::MAX as u64 {
+            self.0.extend_from_slice(&[major | 24, value as u8]);
+        } else if value <= u16::MAX as u64 {
+            self.0.push(major | 25);
+            self.0.extend_from_slice(&(value as u16).to_be_bytes());
+        } else if value <= u32::MAX as u64 {
+            self.0.push(major | 26);
+            self.0.extend_from_slice(&(value as u32).to_be_bytes());
+        } else {
+            self.0.push(major | 27);
+            self.0.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl Packer for CborPacker {
+    fn pack_nil(&mut self) {
+        self.0.push(0xF6);
+    }
+
+    fn pack_bool(&mut self, value: bool) {
+        self.0.push(if value { 0xF5 } else { 0xF4 });
+    }
+
+    fn pack_uint(&mut self, value: u64) {
+        self.pack_head(0, value);
+    }
+
+    fn pack_int(&mut self, value: i64) {
+        if value >= 0 {
+            self.pack_head(0, value as u64);
+        } else {
+            self.pack_head(1, !value as u64);
+        }
+    }
+
+    fn pack_f32(&mut self, value: f32) {
+        self.0.push(0xFA);
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn pack_str(&mut self, value: &str) {
+        self.pack_head(3, value.len() as u64);
+        self.0.extend_from_slice(value.as_bytes());
+    }
+
+    fn pack_bytes(&mut self, value: &[u8]) {
+        self.pack_head(2, value.len() as u64);
+        self.0.extend_from_slice(value);
+    }
+
+    fn pack_array(&mut self, len: usize) {
+        self.pack_head(4, len as u64);
+    }
+
+    fn pack_map(&mut self, len: usize) {
+        self.pack_head(5, len as u64);
+    }
+}
+
+impl Bin {
+    #[cfg(feature = "msgpack")]
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        let mut packer = MsgPacker(Vec::new());
+        packer.pack_bin(self);
+        packer.0
+    }
+
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut packer = CborPacker(Vec::new());
+        packer.pack_bin(self);
+        packer.0
+    }
+}
@@ -0,0 +1,242 @@
+use crate::*;
+
+fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+fn split_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    pointer
+        .strip_prefix('/')
+        .map(|rest| rest.split('/').map(unescape_token).collect())
+}
+
+fn parse_hex(token: &str) -> Option<u64> {
+    let digits = token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))?;
+    u64::from_str_radix(digits, 16).ok()
+}
+
+fn token_name(token: &str) -> Option<BinFNV> {
+    match parse_hex(token) {
+        Some(hash) if hash <= u32::MAX as u64 => Some(BinFNV::from_hash(hash as u32)),
+        Some(_) => None,
+        None => Some(BinFNV::from_hash(fnv(token))),
+    }
+}
+
+fn fnv_matches(name: &BinFNV, token: &str) -> bool {
+    token_name(token).is_some_and(|token| token == *name)
+}
+
+fn xxh_matches(name: &BinXXH, token: &str) -> bool {
+    match parse_hex(token) {
+        Some(hash) => hash == name.get_hash(),
+        None => name.get_string().eq_ignore_ascii_case(token),
+    }
+}
+
+fn key_matches(key: &BinValue, token: &str) -> bool {
+    match key {
+        BinValue::String(value) => value == token,
+        BinValue::Hash(value) | BinValue::Link(value) => fnv_matches(value, token),
+        BinValue::File(value) => xxh_matches(value, token),
+        BinValue::Bool(value) | BinValue::Flag(value) => token.parse::<bool>() == Ok(*value),
+        BinValue::Float(_) => key.to_string() == token,
+        _ => match key.as_i128_checked() {
+            Some(value) => token.parse::<i128>() == Ok(value),
+            None => key.to_string() == token,
+        },
+    }
+}
+
+fn parse_index(token: &str) -> Option<usize> {
+    if token.len() > 1 && token.starts_with('0') {
+        None
+    } else {
+        token.parse::<usize>().ok()
+    }
+}
+
+fn split_query(query: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut rest = query;
+    while !rest.is_empty() {
+        if let Some(inner) = rest.strip_prefix('[') {
+            let end = inner.find(']')?;
+            let token = &inner[..end];
+            let token = token
+                .strip_prefix('"')
+                .and_then(|token| token.strip_suffix('"'))
+                .unwrap_or(token);
+            tokens.push(token.to_string());
+            rest = &inner[end + 1..];
+        } else {
+            let field = rest.strip_prefix('.').unwrap_or(rest);
+            let end = field.find(['.', '[']).unwrap_or(field.len());
+            if end == 0 {
+                return None;
+            }
+            tokens.push(field[..end].to_string());
+            rest = &field[end..];
+        }
+    }
+    Some(tokens)
+}
+
+fn step<'a>(target: &'a BinValue, token: &str) -> Option<&'a BinValue> {
+    match target {
+        BinValue::List(_, items) | BinValue::List2(_, items) => items.get(parse_index(token)?),
+        BinValue::Option(_, Some(value)) if parse_index(token)? == 0 => Some(value),
+        BinValue::Map(_, _, items) => items
+            .iter()
+            .find(|(key, _)| key_matches(key, token))
+            .map(|(_, value)| value),
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => fields.get(&token_name(token)?),
+        _ => None,
+    }
+}
+
+fn step_mut<'a>(target: &'a mut BinValue, token: &str) -> Option<&'a mut BinValue> {
+    match target {
+        BinValue::List(_, items) | BinValue::List2(_, items) => items.get_mut(parse_index(token)?),
+        BinValue::Option(_, Some(value)) if parse_index(token)? == 0 => Some(value),
+        BinValue::Map(_, _, items) => items
+            .iter_mut()
+            .find(|(key, _)| key_matches(key, token))
+            .map(|(_, value)| value),
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => fields.get_mut(&token_name(token)?),
+        _ => None,
+    }
+}
+
+fn export_pointer<'a>(target: &'a BinValue, tokens: &[String]) -> Option<&'a BinValue> {
+    let mut target = target;
+    let mut tokens = tokens.iter();
+    while let Some(token) = tokens.next() {
+        if token != "value" {
+            return None;
+        }
+        target = match target {
+            BinValue::Option(_, value) => value.as_deref()?,
+            BinValue::Map(_, _, items) => {
+                let (key, value) = items.get(parse_index(tokens.next()?)?)?;
+                match tokens.next()?.as_str() {
+                    "0" => key,
+                    "1" => value,
+                    _ => return None,
+                }
+            }
+            _ => step(target, tokens.next()?)?,
+        };
+    }
+    Some(target)
+}
+
+fn export_pointer_mut<'a>(target: &'a mut BinValue, tokens: &[String]) -> Option<&'a mut BinValue> {
+    let mut target = target;
+    let mut tokens = tokens.iter();
+    while let Some(token) = tokens.next() {
+        if token != "value" {
+            return None;
+        }
+        target = match target {
+            BinValue::Option(_, value) => value.as_deref_mut()?,
+            BinValue::Map(_, _, items) => {
+                let (key, value) = items.get_mut(parse_index(tokens.next()?)?)?;
+                match tokens.next()?.as_str() {
+                    "0" => key,
+                    "1" => value,
+                    _ => return None,
+                }
+            }
+            _ => step_mut(target, tokens.next()?)?,
+        };
+    }
+    Some(target)
+}
+
+impl BinValue {
+    pub fn pointer(&self, pointer: &str) -> Option<&BinValue> {
+        let mut target = self;
+        for token in split_pointer(pointer)? {
+            target = step(target, &token)?;
+        }
+        Some(target)
+    }
+
+    pub fn select(&self, query: &str) -> Option<&BinValue> {
+        let mut target = self;
+        for token in split_query(query)? {
+            target = step(target, &token)?;
+        }
+        Some(target)
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut BinValue> {
+        let mut target = self;
+        for token in split_pointer(pointer)? {
+            target = step_mut(target, &token)?;
+        }
+        Some(target)
+    }
+
+    pub fn select_mut(&mut self, query: &str) -> Option<&mut BinValue> {
+        let mut target = self;
+        for token in split_query(query)? {
+            target = step_mut(target, &token)?;
+        }
+        Some(target)
+    }
+}
+
+impl Bin {
+    pub fn pointer(&self, pointer: &str) -> Option<&BinValue> {
+        let tokens = split_pointer(pointer)?;
+        match tokens.as_slice() {
+            [section, entry, rest @ ..] if section == "entries" => {
+                export_pointer(self.entries.get(&token_name(entry)?)?, rest)
+            }
+            [entry, rest @ ..] => {
+                let mut target = self.entries.get(&token_name(entry)?)?;
+                for token in rest {
+                    target = step(target, token)?;
+                }
+                Some(target)
+            }
+            [] => None,
+        }
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut BinValue> {
+        let tokens = split_pointer(pointer)?;
+        match tokens.as_slice() {
+            [section, entry, rest @ ..] if section == "entries" => {
+                export_pointer_mut(self.entries.get_mut(&token_name(entry)?)?, rest)
+            }
+            [entry, rest @ ..] => {
+                let mut target = self.entries.get_mut(&token_name(entry)?)?;
+                for token in rest {
+                    target = step_mut(target, token)?;
+                }
+                Some(target)
+            }
+            [] => None,
+        }
+    }
+
+    pub fn select(&self, query: &str) -> Option<&BinValue> {
+        let end = query.find(['.', '[']).unwrap_or(query.len());
+        let (entry, rest) = query.split_at(end);
+        self.entries.get(&token_name(entry)?)?.select(rest)
+    }
+
+    pub fn select_mut(&mut self, query: &str) -> Option<&mut BinValue> {
+        let end = query.find(['.', '[']).unwrap_or(query.len());
+        let (entry, rest) = query.split_at(end);
+        self.entries.get_mut(&token_name(entry)?)?.select_mut(rest)
+    }
+}
@@ -0,0 +1,128 @@
+use crate::*;
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Clone, Debug, Default)]
+pub struct BinProject {
+    pub base: HashMap<String, Bin>,
+    pub overlay: HashMap<String, Bin>,
+}
+
+impl BinProject {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn effective(&self, path: &str) -> Option<Bin> {
+        match (self.base.get(path), self.overlay.get(path)) {
+            (Some(base), Some(overlay)) => {
+                let mut result = base.clone();
+                for link in &overlay.links {
+                    if !result.links.contains(link) {
+                        result.links.push(link.clone());
+                    }
+                }
+                for (name, value) in &overlay.entries {
+                    result.entries.insert(*name, value.clone());
+                }
+                Some(result)
+            }
+            (Some(bin), None) | (None, Some(bin)) => Some(bin.clone()),
+            (None, None) => None,
+        }
+    }
+
+    pub fn effective_entry(&self, path: &str, name: &BinFNV) -> Option<&BinValue> {
+        self.overlay
+            .get(path)
+            .and_then(|bin| bin.entries.get(name))
+            .or_else(|| self.base.get(path).and_then(|bin| bin.entries.get(name)))
+    }
+
+    pub fn minimal_overlay(&self) -> HashMap<String, Bin> {
+        let mut result = HashMap::new();
+        for (path, overlay) in &self.overlay {
+            let base = match self.base.get(path) {
+                Some(base) => base,
+                None => {
+                    result.insert(path.clone(), overlay.clone());
+                    continue;
+                }
+            };
+            let mut minimal = overlay.clone();
+            minimal.entries.retain(|name, value| match base.entries.get(name) {
+                Some(base_value) => base_value.checksum() != value.checksum(),
+                None => true,
+            });
+            if !minimal.entries.is_empty() {
+                result.insert(path.clone(), minimal);
+            }
+        }
+        result
+    }
+
+    fn resolve_path(&self, link: &str) -> Option<&String> {
+        self.overlay
+            .keys()
+            .chain(self.base.keys())
+            .find(|path| path.eq_ignore_ascii_case(link))
+    }
+
+    pub fn link_closure(&self, path: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        let mut pending = VecDeque::new();
+        pending.push_back(path.to_string());
+        while let Some(link) = pending.pop_front() {
+            let path = match self.resolve_path(&link) {
+                Some(path) => path,
+                None => continue,
+            };
+            if !seen.insert(path.to_ascii_lowercase()) {
+                continue;
+            }
+            result.push(path.clone());
+            let links = self.overlay.get(path).into_iter().chain(self.base.get(path));
+            for bin in links {
+                pending.extend(bin.links.iter().cloned());
+            }
+        }
+        result
+    }
+
+    pub fn entry_sources(&self, path: &str) -> HashMap<BinFNV, String> {
+        let mut result = HashMap::new();
+        for link in self.link_closure(path) {
+            if let Some(bin) = self.effective(&link) {
+                for name in bin.entries.keys() {
+                    result.entry(*name).or_insert_with(|| link.clone());
+                }
+            }
+        }
+        result
+    }
+
+    pub fn entries_in_link(&self, path: &str, link: &str) -> Vec<BinFNV> {
+        let subtree: HashSet<String> = self.link_closure(link).into_iter().collect();
+        let mut result: Vec<BinFNV> = self
+            .entry_sources(path)
+            .into_iter()
+            .filter(|(_, source)| subtree.contains(source))
+            .map(|(name, _)| name)
+            .collect();
+        result.sort();
+        result
+    }
+
+    pub fn assemble(&self, path: &str) -> Option<Bin> {
+        let mut links = self.link_closure(path).into_iter();
+        let mut result = self.effective(&links.next()?)?;
+        for link in links {
+            if let Some(bin) = self.effective(&link) {
+                for (name, value) in bin.entries {
+                    result.entries.entry(name).or_insert(value);
+                }
+            }
+        }
+        Some(result)
+    }
+}
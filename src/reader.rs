@@ -1,104 +1,102 @@
 use crate::*;
-use num_enum::TryFromPrimitive;
 use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::io::{Cursor, Error, ErrorKind, Read, Result, Seek, SeekFrom};
-
-#[derive(TryFromPrimitive, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
-#[repr(u8)]
-enum BinType {
-    None = 0,
-    Bool = 1,
-    I8 = 2,
-    U8 = 3,
-    I16 = 4,
-    U16 = 5,
-    I32 = 6,
-    U32 = 7,
-    I64 = 8,
-    U64 = 9,
-    F32 = 10,
-    Vec2 = 11,
-    Vec3 = 12,
-    Vec4 = 13,
-    Mtx44 = 14,
-    Rgba = 15,
-    String = 16,
-    Hash = 17,
-    File = 18,
-    List = 0x80 | 0,
-    List2 = 0x80 | 1,
-    Pointer = 0x80 | 2,
-    Embed = 0x80 | 3,
-    Link = 0x80 | 4,
-    Option = 0x80 | 5,
-    Map = 0x80 | 6,
-    Flag = 0x80 | 7,
+use std::io::{Cursor, Read};
+
+type Result<T> = std::result::Result<T, BinError>;
+
+const LEGACY_LIST: u8 = 18;
+
+fn in_path<T, F>(result: Result<T>, segment: F) -> Result<T>
+where
+    F: FnOnce() -> String,
+{
+    result.map_err(|error| error.in_path(segment()))
 }
 
 pub struct BinReader<'a, 'b> {
     cur: Cursor<&'a [u8]>,
     depth: usize,
     hashes: &'b BinHashes,
+    options: &'b ReadOptions,
 }
 
 impl<'a, 'b> BinReader<'a, 'b> {
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let offset = self.cur.position();
+        let mut buffer = [0; N];
+        self.cur
+            .read_exact(&mut buffer)
+            .map_err(|_| BinError::UnexpectedEof { offset })?;
+        Ok(buffer)
+    }
+
+    fn check_end(&self) -> Result<()> {
+        let offset = self.cur.position();
+        if offset < self.cur.get_ref().len() as u64 {
+            Err(BinError::TrailingData { offset })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_limit(&self, what: &'static str, len: usize, limit: usize) -> Result<()> {
+        if len > limit {
+            Err(BinError::LimitExceeded {
+                offset: self.cur.position(),
+                what,
+                len,
+                limit,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     fn read_i8(&mut self) -> Result<i8> {
-        let mut buffer = [0; 1];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(i8::from_le_bytes(buffer))
+        Ok(i8::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_u8(&mut self) -> Result<u8> {
-        let mut buffer = [0; 1];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(u8::from_le_bytes(buffer))
+        Ok(u8::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_i16(&mut self) -> Result<i16> {
-        let mut buffer = [0; 2];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(i16::from_le_bytes(buffer))
+        Ok(i16::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_u16(&mut self) -> Result<u16> {
-        let mut buffer = [0; 2];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(u16::from_le_bytes(buffer))
+        Ok(u16::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_i32(&mut self) -> Result<i32> {
-        let mut buffer = [0; 4];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(i32::from_le_bytes(buffer))
+        Ok(i32::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_u32(&mut self) -> Result<u32> {
-        let mut buffer = [0; 4];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(u32::from_le_bytes(buffer))
+        Ok(u32::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_i64(&mut self) -> Result<i64> {
-        let mut buffer = [0; 8];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(i64::from_le_bytes(buffer))
+        Ok(i64::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_u64(&mut self) -> Result<u64> {
-        let mut buffer = [0; 8];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(u64::from_le_bytes(buffer))
+        Ok(u64::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_f32(&mut self) -> Result<f32> {
-        let mut buffer = [0; 4];
-        self.cur.read_exact(&mut buffer)?;
-        Ok(f32::from_le_bytes(buffer))
+        Ok(f32::from_le_bytes(self.read_bytes()?))
     }
 
     fn read_type(&mut self) -> Result<BinType> {
-        Ok(BinType::try_from(self.read_u8()?).unwrap())
+        let offset = self.cur.position();
+        let type_id = self.read_u8()?;
+        let type_id = match self.options.format {
+            BinFormat::Legacy if (LEGACY_LIST..0x80).contains(&type_id) => type_id - LEGACY_LIST + 0x80,
+            _ => type_id,
+        };
+        BinType::try_from(type_id).map_err(|_| BinError::UnknownType { offset, type_id })
     }
 
     fn read_vec2(&mut self) -> Result<[f32; 2]> {
@@ -139,13 +137,18 @@ impl<'a, 'b> BinReader<'a, 'b> {
     }
 
     fn read_string(&mut self) -> Result<String> {
-        let len = self.read_u16()?;
-        let cur_pos = self.cur.position() as usize;
-        let end_pos = self.cur.seek(SeekFrom::Current(len as i64))? as usize;
-        let mut cur = Cursor::new(&self.cur.get_ref()[cur_pos..end_pos]);
-        let mut result = String::new();
-        cur.read_to_string(&mut result)?;
-        Ok(result)
+        let len = self.read_u16()? as usize;
+        self.check_limit("String length", len, self.options.max_string_len)?;
+        let offset = self.cur.position();
+        let cur_pos = offset as usize;
+        let end_pos = cur_pos + len;
+        let data = *self.cur.get_ref();
+        let bytes = data
+            .get(cur_pos..end_pos)
+            .ok_or(BinError::TruncatedString { offset, len })?;
+        self.cur.set_position(end_pos as u64);
+        let string = std::str::from_utf8(bytes).map_err(|_| BinError::InvalidString { offset })?;
+        Ok(string.to_string())
     }
 
     fn read_hash_name(&mut self) -> Result<BinFNV> {
@@ -176,33 +179,52 @@ impl<'a, 'b> BinReader<'a, 'b> {
     fn read_sub_reader(&mut self) -> Result<BinReader<'a, 'b>> {
         let depth = self.depth + 1;
         if depth > 128 {
-            Err(Error::new(
-                ErrorKind::Other,
-                "Sub reader depth limit reached",
-            ))
+            Err(BinError::DepthLimit {
+                offset: self.cur.position(),
+            })
         } else {
-            let len = self.read_u32()? as i64;
+            let offset = self.cur.position();
+            let len = self.read_u32()? as usize;
             let cur_pos = self.cur.position();
-            let end_pos = self.cur.seek(SeekFrom::Current(len))? as usize;
+            let end_pos = cur_pos as usize + len;
+            if end_pos > self.cur.get_ref().len() {
+                return Err(BinError::UnexpectedEof { offset });
+            }
+            self.cur.set_position(end_pos as u64);
             let mut cur = Cursor::new(&self.cur.get_ref()[..end_pos]);
             cur.set_position(cur_pos);
             Ok(BinReader {
                 cur,
                 depth,
                 hashes: self.hashes,
+                options: self.options,
             })
         }
     }
 
-    fn read_fields(&mut self) -> Result<HashMap<BinFNV, BinValue>> {
-        let mut result = HashMap::new();
+    fn read_fields(&mut self) -> Result<IndexMap<BinFNV, BinValue>> {
+        let mut result = IndexMap::new();
         let count = self.read_u16()?;
-        for _ in 0..count {
+        self.check_limit("Field count", count as usize, self.options.max_fields)?;
+        for index in 0..count {
             let key = self.read_field_name()?;
-            let value_type = self.read_type()?;
-            let value = self.read_value(value_type)?;
-            result.insert(key, value);
+            let offset = self.cur.position() as usize;
+            let value = self.read_type().and_then(|value_type| self.read_value(value_type));
+            match value {
+                Err(error) if self.options.recover_unknown && error.is_unknown_type() => {
+                    let data = *self.cur.get_ref();
+                    self.cur.set_position(data.len() as u64);
+                    let trailing = count - index - 1;
+                    result.insert(key, BinValue::Unknown(data[offset], data[offset + 1..].to_vec(), trailing));
+                    break;
+                }
+                value => {
+                    let value = in_path(value, || format!(".{:?}", key))?;
+                    result.insert(key, value);
+                }
+            }
         }
+        self.check_end()?;
         Ok(result)
     }
 
@@ -210,15 +232,16 @@ impl<'a, 'b> BinReader<'a, 'b> {
         let io = self;
         Ok(match bin_type {
             BinType::None => BinValue::None,
-            BinType::Bool | BinType::Flag => BinValue::Bool(io.read_u8()? != 0),
-            BinType::I8 => BinValue::Signed(io.read_i8()? as i64),
-            BinType::U8 => BinValue::Unsigned(io.read_u8()? as u64),
-            BinType::I16 => BinValue::Signed(io.read_i16()? as i64),
-            BinType::U16 => BinValue::Unsigned(io.read_u16()? as u64),
-            BinType::I32 => BinValue::Signed(io.read_i32()? as i64),
-            BinType::U32 => BinValue::Unsigned(io.read_u32()? as u64),
-            BinType::I64 => BinValue::Signed(io.read_i64()? as i64),
-            BinType::U64 => BinValue::Unsigned(io.read_u64()? as u64),
+            BinType::Bool => BinValue::Bool(io.read_u8()? != 0),
+            BinType::Flag => BinValue::Flag(io.read_u8()? != 0),
+            BinType::I8 => BinValue::I8(io.read_i8()?),
+            BinType::U8 => BinValue::U8(io.read_u8()?),
+            BinType::I16 => BinValue::I16(io.read_i16()?),
+            BinType::U16 => BinValue::U16(io.read_u16()?),
+            BinType::I32 => BinValue::I32(io.read_i32()?),
+            BinType::U32 => BinValue::U32(io.read_u32()?),
+            BinType::I64 => BinValue::I64(io.read_i64()?),
+            BinType::U64 => BinValue::U64(io.read_u64()?),
             BinType::F32 => BinValue::Float(io.read_f32()?),
             BinType::Vec2 => BinValue::Vec2(io.read_vec2()?),
             BinType::Vec3 => BinValue::Vec3(io.read_vec3()?),
@@ -233,61 +256,107 @@ impl<'a, 'b> BinReader<'a, 'b> {
                 let value_type = io.read_type()?;
                 let count = io.read_u8()?;
                 if count == 0 {
-                    BinValue::None
+                    BinValue::Option(value_type, None)
                 } else {
-                    io.read_value(value_type)?
+                    BinValue::Option(value_type, Some(Box::new(io.read_value(value_type)?)))
                 }
             }
             BinType::List | BinType::List2 => {
                 let value_type = io.read_type()?;
                 let mut io = io.read_sub_reader()?;
                 let count = io.read_u32()?;
+                io.check_limit("List length", count as usize, io.options.max_list_len)?;
                 let mut result = Vec::new();
-                for _ in 0..count {
-                    result.push(io.read_value(value_type)?)
+                for index in 0..count {
+                    let value = in_path(io.read_value(value_type), || format!("[{}]", index))?;
+                    result.push(value)
+                }
+                io.check_end()?;
+                if bin_type == BinType::List {
+                    BinValue::List(value_type, result)
+                } else {
+                    BinValue::List2(value_type, result)
                 }
-                BinValue::List(result)
             }
             BinType::Map => {
                 let key_type = io.read_type()?;
                 let value_type = io.read_type()?;
                 let mut io = io.read_sub_reader()?;
                 let count = io.read_u32()?;
+                io.check_limit("Map length", count as usize, io.options.max_map_len)?;
                 let mut result = Vec::new();
-                for _ in 0..count {
-                    let key = io.read_value(key_type)?;
-                    let value = io.read_value(value_type)?;
+                for index in 0..count {
+                    let key = in_path(io.read_value(key_type), || format!("[{}]", index))?;
+                    let value = in_path(io.read_value(value_type), || format!("[{:?}]", key))?;
                     result.push((key, value))
                 }
-                BinValue::Map(result)
+                io.check_end()?;
+                BinValue::Map(key_type, value_type, result)
             }
             BinType::Pointer | BinType::Embed => {
                 let type_name = io.read_type_name()?;
                 if type_name.get_hash() == 0 {
-                    BinValue::None
+                    if bin_type == BinType::Pointer {
+                        BinValue::Struct(type_name, IndexMap::new())
+                    } else {
+                        BinValue::Embed(type_name, IndexMap::new())
+                    }
                 } else {
                     let mut io = io.read_sub_reader()?;
                     let fields = io.read_fields()?;
-                    BinValue::Struct(type_name, fields)
+                    if bin_type == BinType::Pointer {
+                        BinValue::Struct(type_name, fields)
+                    } else {
+                        BinValue::Embed(type_name, fields)
+                    }
                 }
             }
         })
     }
 
-    fn read_entries(&mut self) -> Result<HashMap<BinFNV, BinValue>> {
+    #[cfg(feature = "tools")]
+    pub(crate) fn try_read_value(
+        data: &'a [u8],
+        hashes: &'b BinHashes,
+        options: &'b ReadOptions,
+        bin_type: BinType,
+    ) -> Option<(BinValue, usize)> {
+        let mut io = BinReader {
+            cur: Cursor::new(data),
+            depth: 0,
+            hashes,
+            options,
+        };
+        let value = io.read_value(bin_type).ok()?;
+        Some((value, io.cur.position() as usize))
+    }
+
+    fn read_entries(&mut self, errors: &mut Vec<(BinFNV, BinError)>) -> Result<IndexMap<BinFNV, BinValue>> {
         let count = self.read_u32()?;
+        self.check_limit("Entry count", count as usize, self.options.max_entries)?;
         let mut type_names = Vec::new();
         for _ in 0..count {
             let type_name = self.read_type_name()?;
             type_names.push(type_name);
         }
-        let mut result = HashMap::new();
+        let mut result = IndexMap::new();
         for type_name in type_names {
             let mut io = self.read_sub_reader()?;
-            let key = io.read_entry_name()?;
-            let fields = io.read_fields()?;
-            let value = BinValue::Struct(type_name, fields);
-            result.insert(key, value);
+            let key = match io.read_entry_name() {
+                Ok(key) => key,
+                Err(error) if !self.options.strict => {
+                    errors.push((BinFNV::from_hash(0), error));
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+            match in_path(io.read_fields(), || format!("{:?}", key)) {
+                Ok(fields) => {
+                    result.insert(key, BinValue::Embed(type_name, fields));
+                }
+                Err(error) if !self.options.strict => errors.push((key, error)),
+                Err(error) => return Err(error),
+            }
         }
         Ok(result)
     }
@@ -295,32 +364,184 @@ impl<'a, 'b> BinReader<'a, 'b> {
     fn read_links(&mut self) -> Result<Vec<String>> {
         let count = self.read_u32()?;
         let mut result = Vec::new();
-        for _ in 0..count {
-            let value = self.read_string()?;
+        for index in 0..count {
+            let value = in_path(self.read_string(), || format!("links[{}]", index))?;
             result.push(value);
         }
         Ok(result)
     }
 
-    pub fn read_bin(data: &[u8], hashes: &BinHashes) -> Result<Bin> {
+    fn read_patch(&mut self) -> Result<(String, BinValue)> {
+        let value_type = self.read_type()?;
+        let path = self.read_string()?;
+        let value = self.read_value(value_type)?;
+        self.check_end()?;
+        Ok((path, value))
+    }
+
+    fn read_patches(&mut self, errors: &mut Vec<(BinFNV, BinError)>) -> Result<Vec<BinPatch>> {
+        let count = self.read_u32()?;
+        let mut result = Vec::new();
+        for _ in 0..count {
+            let name = self.read_entry_name()?;
+            let mut io = self.read_sub_reader()?;
+            match in_path(io.read_patch(), || format!("{:?}", name)) {
+                Ok((path, value)) => result.push(BinPatch { name, path, value }),
+                Err(error) if !self.options.strict => errors.push((name, error)),
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(result)
+    }
+
+    fn read_header(&mut self) -> Result<(bool, u32, Vec<String>)> {
+        let offset = self.cur.position();
+        let mut magic = self.read_u32()?;
+        let is_patch = magic == 0x48435450;
+        if is_patch {
+            self.read_u64()?;
+            magic = self.read_u32()?;
+        }
+        if magic == 0x504f5250 {
+            let version = self.read_u32()?;
+            let links = self.read_links()?;
+            Ok((is_patch, version, links))
+        } else {
+            Err(BinError::BadMagic { offset, magic })
+        }
+    }
+
+    fn read_bin_with_format(data: &[u8], hashes: &BinHashes, options: &ReadOptions) -> Result<Bin> {
         let cur = Cursor::new(data);
         let mut reader = BinReader {
             cur,
             depth: 0,
             hashes,
+            options,
         };
-        let magic = reader.read_u32()?;
-        if magic == 0x504f5250 {
-            let version = reader.read_u32()?;
-            let links = reader.read_links()?;
-            let entries = reader.read_entries()?;
-            Ok(Bin {
-                version,
-                links,
-                entries,
-            })
+        let (is_patch, version, links) = reader.read_header()?;
+        let mut errors = Vec::new();
+        let entries = reader.read_entries(&mut errors)?;
+        let patches = if is_patch && version >= 3 {
+            reader.read_patches(&mut errors)?
         } else {
-            Err(Error::new(ErrorKind::Other, "Bad bin magic"))
+            Vec::new()
+        };
+        let tail = data[reader.cur.position() as usize..].to_vec();
+        Ok(Bin {
+            is_patch,
+            version,
+            links,
+            entries,
+            patches,
+            tail,
+            errors,
+        })
+    }
+
+    pub fn read_bin(data: &[u8], hashes: &BinHashes, options: &ReadOptions) -> Result<Bin> {
+        if options.format != BinFormat::Auto {
+            return Self::read_bin_with_format(data, hashes, options);
         }
+        let modern = ReadOptions {
+            format: BinFormat::Modern,
+            ..options.clone()
+        };
+        match Self::read_bin_with_format(data, hashes, &modern) {
+            Ok(bin) => Ok(bin),
+            Err(error) => {
+                let legacy = ReadOptions {
+                    format: BinFormat::Legacy,
+                    ..options.clone()
+                };
+                Self::read_bin_with_format(data, hashes, &legacy).map_err(|_| error)
+            }
+        }
+    }
+}
+
+pub struct BinLazyReader<'a, 'b> {
+    data: &'a [u8],
+    hashes: &'b BinHashes,
+    options: &'b ReadOptions,
+    pub is_patch: bool,
+    pub version: u32,
+    pub links: Vec<String>,
+    names: Vec<BinFNV>,
+    offsets: HashMap<BinFNV, (BinFNV, u64)>,
+}
+
+impl<'a, 'b> BinLazyReader<'a, 'b> {
+    pub fn new(data: &'a [u8], hashes: &'b BinHashes, options: &'b ReadOptions) -> Result<Self> {
+        let mut reader = BinReader {
+            cur: Cursor::new(data),
+            depth: 0,
+            hashes,
+            options,
+        };
+        let (is_patch, version, links) = reader.read_header()?;
+        let count = reader.read_u32()?;
+        reader.check_limit("Entry count", count as usize, options.max_entries)?;
+        let mut type_names = Vec::new();
+        for _ in 0..count {
+            type_names.push(reader.read_type_name()?);
+        }
+        let mut names = Vec::new();
+        let mut offsets = HashMap::new();
+        for type_name in type_names {
+            let offset = reader.cur.position();
+            let name = reader.read_sub_reader()?.read_entry_name()?;
+            names.push(name);
+            offsets.insert(name, (type_name, offset));
+        }
+        Ok(Self {
+            data,
+            hashes,
+            options,
+            is_patch,
+            version,
+            links,
+            names,
+            offsets,
+        })
+    }
+
+    pub fn names(&self) -> &[BinFNV] {
+        &self.names
+    }
+
+    pub fn entry_type(&self, name: &BinFNV) -> Option<&BinFNV> {
+        self.offsets.get(name).map(|(type_name, _)| type_name)
+    }
+
+    fn entry_reader(&self, name: &BinFNV) -> Result<(BinFNV, BinReader<'a, 'b>)> {
+        let (type_name, offset) = self
+            .offsets
+            .get(name)
+            .ok_or_else(|| BinError::EntryNotFound {
+                name: format!("{:?}", name),
+            })?;
+        let mut cur = Cursor::new(self.data);
+        cur.set_position(*offset);
+        let mut reader = BinReader {
+            cur,
+            depth: 0,
+            hashes: self.hashes,
+            options: self.options,
+        };
+        Ok((*type_name, reader.read_sub_reader()?))
+    }
+
+    pub fn entry(&self, name: &BinFNV) -> Result<BinValue> {
+        let (type_name, mut io) = self.entry_reader(name)?;
+        let key = io.read_entry_name()?;
+        let fields = in_path(io.read_fields(), || format!("{:?}", key))?;
+        Ok(BinValue::Embed(type_name, fields))
+    }
+
+    pub fn entry_raw_bytes(&self, name: &BinFNV) -> Result<&'a [u8]> {
+        let (_, io) = self.entry_reader(name)?;
+        let data: &'a [u8] = io.cur.get_ref();
+        Ok(&data[io.cur.position() as usize..])
     }
 }
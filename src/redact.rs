@@ -0,0 +1,68 @@
+use crate::*;
+
+pub fn redact_string(string: &str) -> String {
+    if string.is_empty() {
+        String::new()
+    } else {
+        format!("redacted_{:08x}", fnv(string))
+    }
+}
+
+impl BinValue {
+    pub fn redact(&mut self) {
+        match self {
+            BinValue::String(value) => *value = redact_string(value),
+            BinValue::Hash(name) | BinValue::Link(name) => *name = BinFNV::from_hash(name.get_hash()),
+            BinValue::File(file) => *file = BinXXH::from_hash(file.get_hash()),
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                for item in items {
+                    item.redact();
+                }
+            }
+            BinValue::Option(_, Some(item)) => item.redact(),
+            BinValue::Map(_, _, items) => {
+                for (key, value) in items {
+                    key.redact();
+                    value.redact();
+                }
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                *name = BinFNV::from_hash(name.get_hash());
+                *fields = fields
+                    .drain(..)
+                    .map(|(name, mut value)| {
+                        value.redact();
+                        (BinFNV::from_hash(name.get_hash()), value)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Bin {
+    pub fn redact(&mut self) {
+        for link in &mut self.links {
+            *link = redact_string(link);
+        }
+        self.entries = self
+            .entries
+            .drain(..)
+            .map(|(name, mut value)| {
+                value.redact();
+                (BinFNV::from_hash(name.get_hash()), value)
+            })
+            .collect();
+        for patch in &mut self.patches {
+            patch.name = BinFNV::from_hash(patch.name.get_hash());
+            patch.path = redact_string(&patch.path);
+            patch.value.redact();
+        }
+        self.errors = self
+            .errors
+            .drain(..)
+            .map(|(name, error)| (BinFNV::from_hash(name.get_hash()), error))
+            .collect();
+    }
+}
@@ -0,0 +1,39 @@
+use crate::*;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+pub const CDTB_HASHES_URL: &str = "https://raw.communitydragon.org/data/hashes/lol";
+
+const HASH_FILES: [&str; 5] = [
+    "hashes.binentries.txt",
+    "hashes.binfields.txt",
+    "hashes.binhashes.txt",
+    "hashes.bintypes.txt",
+    "hashes.game.txt",
+];
+
+pub fn download_hashes<P: AsRef<Path>>(base_url: &str, dir: P) -> Result<(), String> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    for name in HASH_FILES {
+        let url = format!("{}/{}", base_url.trim_end_matches('/'), name);
+        let response = ureq::get(&url).call().map_err(|e| e.to_string())?;
+        let mut data = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut data)
+            .map_err(|e| format!("{}: {}", url, e))?;
+        let tmp = dir.join(format!("{}.tmp", name));
+        fs::write(&tmp, &data).map_err(|e| format!("{}: {}", tmp.display(), e))?;
+        fs::rename(&tmp, dir.join(name)).map_err(|e| format!("{}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+impl BinHashes {
+    pub fn update_from_remote<P: AsRef<Path>>(dir: P) -> Result<BinHashes, String> {
+        download_hashes(CDTB_HASHES_URL, &dir)?;
+        BinHashes::load_from_dir(dir)
+    }
+}
@@ -0,0 +1,91 @@
+use crate::*;
+use std::fmt::{Formatter, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+pub type BinClassRender = fn(&BinFNV, &IndexMap<BinFNV, BinValue>, &mut Formatter<'_>) -> Result;
+
+#[derive(Clone, Debug, Default)]
+pub struct BinClassDisplay {
+    pub name: Option<String>,
+    pub render: Option<BinClassRender>,
+}
+
+type Registry = HashMap<u32, Arc<BinClassDisplay>>;
+
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+fn registry() -> &'static RwLock<Registry> {
+    static CLASS_DISPLAY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    CLASS_DISPLAY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn with_registry<F: FnOnce(&mut Registry)>(f: F) {
+    let mut registry = registry().write().unwrap_or_else(|error| error.into_inner());
+    f(&mut registry);
+    REGISTERED.store(!registry.is_empty(), Ordering::Release);
+}
+
+fn render_inline(name: &BinFNV, fields: &IndexMap<BinFNV, BinValue>, f: &mut Formatter<'_>) -> Result {
+    if fields.is_empty() {
+        write_class_label(name, f)?;
+        return write!(f, " {{}}");
+    }
+    write_class_label(name, f)?;
+    write!(f, " {{")?;
+    for (index, (name, value)) in fields.iter().enumerate() {
+        let separator = if index == 0 { " " } else { ", " };
+        write!(f, "{}{}: {:?}", separator, name_label(name), value)?;
+    }
+    write!(f, " }}")
+}
+
+fn write_class_label(name: &BinFNV, f: &mut Formatter<'_>) -> Result {
+    match BinClassDisplay::get(name.get_hash()).as_deref().and_then(|display| display.name.as_deref()) {
+        Some(label) => f.write_str(label),
+        None => f.write_str(&name_label(name)),
+    }
+}
+
+impl BinClassDisplay {
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            render: None,
+        }
+    }
+
+    pub fn inline() -> Self {
+        Self {
+            name: None,
+            render: Some(render_inline),
+        }
+    }
+
+    pub fn get(class: u32) -> Option<Arc<Self>> {
+        if !REGISTERED.load(Ordering::Acquire) {
+            return None;
+        }
+        registry().read().unwrap_or_else(|error| error.into_inner()).get(&class).cloned()
+    }
+
+    pub fn register(class: &str, display: Self) {
+        Self::register_hash(fnv(class), display);
+    }
+
+    pub fn register_hash(class: u32, display: Self) {
+        with_registry(|registry| {
+            registry.insert(class, Arc::new(display));
+        });
+    }
+
+    pub fn unregister(class: &str) {
+        with_registry(|registry| {
+            registry.remove(&fnv(class));
+        });
+    }
+
+    pub fn clear() {
+        with_registry(Registry::clear);
+    }
+}
@@ -0,0 +1,351 @@
+use crate::*;
+use serde::de::{self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const VALUE_VARIANTS: &[&str] = &[
+    "none", "bool", "i8", "u8", "i16", "u16", "i32", "u32", "i64", "u64", "f32", "vec2", "vec3", "vec4", "mtx44",
+    "rgba", "string", "hash", "link", "file", "list", "list2", "map", "pointer", "embed", "flag", "unknown",
+    "option",
+];
+
+const BIN_FIELDS: &[&str] = &["is_patch", "version", "links", "entries", "patches", "tail"];
+
+const PATCH_FIELDS: &[&str] = &["name", "path", "value"];
+
+struct IdentifierSeed(&'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for IdentifierSeed {
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for IdentifierSeed {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "one of {:?}", self.0)
+    }
+
+    fn visit_u64<E: de::Error>(self, index: u64) -> Result<usize, E> {
+        match self.0.get(index as usize) {
+            Some(_) => Ok(index as usize),
+            None => Err(E::invalid_value(de::Unexpected::Unsigned(index), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<usize, E> {
+        self.0
+            .iter()
+            .position(|candidate| *candidate == name)
+            .ok_or_else(|| E::unknown_variant(name, self.0))
+    }
+
+    fn visit_bytes<E: de::Error>(self, name: &[u8]) -> Result<usize, E> {
+        match std::str::from_utf8(name) {
+            Ok(name) => self.visit_str(name),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(name), &self)),
+        }
+    }
+}
+
+fn parse_hex<T: num_traits::Num>(string: &str) -> Option<T> {
+    T::from_str_radix(string.strip_prefix("0x")?, 16).ok()
+}
+
+impl Serialize for BinFNV {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.get_string().is_empty() {
+            serializer.serialize_str(&format!("0x{:08X}", self.get_hash()))
+        } else {
+            serializer.serialize_str(self.get_string())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BinFNV {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        Ok(match parse_hex(&string) {
+            Some(hash) => BinFNV::from_hash(hash),
+            None => BinFNV::from_hash_string(fnv(&string), &string),
+        })
+    }
+}
+
+impl Serialize for BinXXH {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.get_string().is_empty() {
+            serializer.serialize_str(&format!("0x{:016X}", self.get_hash()))
+        } else {
+            serializer.serialize_str(self.get_string())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BinXXH {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        Ok(match parse_hex(&string) {
+            Some(hash) => BinXXH::from_hash(hash),
+            None => BinXXH::from_hash_string(xxh64(&string), &string),
+        })
+    }
+}
+
+impl Serialize for BinType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for BinType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        BinType::from_name(&string)
+            .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(&string), &"a bin type name"))
+    }
+}
+
+impl Serialize for BinValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let s = serializer;
+        match self {
+            BinValue::None => s.serialize_unit_variant("BinValue", 0, "none"),
+            BinValue::Bool(value) => s.serialize_newtype_variant("BinValue", 1, "bool", value),
+            BinValue::I8(value) => s.serialize_newtype_variant("BinValue", 2, "i8", value),
+            BinValue::U8(value) => s.serialize_newtype_variant("BinValue", 3, "u8", value),
+            BinValue::I16(value) => s.serialize_newtype_variant("BinValue", 4, "i16", value),
+            BinValue::U16(value) => s.serialize_newtype_variant("BinValue", 5, "u16", value),
+            BinValue::I32(value) => s.serialize_newtype_variant("BinValue", 6, "i32", value),
+            BinValue::U32(value) => s.serialize_newtype_variant("BinValue", 7, "u32", value),
+            BinValue::I64(value) => s.serialize_newtype_variant("BinValue", 8, "i64", value),
+            BinValue::U64(value) => s.serialize_newtype_variant("BinValue", 9, "u64", value),
+            BinValue::Float(value) => s.serialize_newtype_variant("BinValue", 10, "f32", value),
+            BinValue::Vec2(value) => s.serialize_newtype_variant("BinValue", 11, "vec2", value),
+            BinValue::Vec3(value) => s.serialize_newtype_variant("BinValue", 12, "vec3", value),
+            BinValue::Vec4(value) => s.serialize_newtype_variant("BinValue", 13, "vec4", value),
+            BinValue::Mtx44(value) => s.serialize_newtype_variant("BinValue", 14, "mtx44", value),
+            BinValue::Rgba(value) => s.serialize_newtype_variant("BinValue", 15, "rgba", value),
+            BinValue::String(value) => s.serialize_newtype_variant("BinValue", 16, "string", value),
+            BinValue::Hash(value) => s.serialize_newtype_variant("BinValue", 17, "hash", value),
+            BinValue::Link(value) => s.serialize_newtype_variant("BinValue", 18, "link", value),
+            BinValue::File(value) => s.serialize_newtype_variant("BinValue", 19, "file", value),
+            BinValue::List(value_type, items) => {
+                s.serialize_newtype_variant("BinValue", 20, "list", &(value_type, items))
+            }
+            BinValue::List2(value_type, items) => {
+                s.serialize_newtype_variant("BinValue", 21, "list2", &(value_type, items))
+            }
+            BinValue::Map(key_type, value_type, items) => {
+                s.serialize_newtype_variant("BinValue", 22, "map", &(key_type, value_type, items))
+            }
+            BinValue::Struct(name, fields) => {
+                s.serialize_newtype_variant("BinValue", 23, "pointer", &(name, fields))
+            }
+            BinValue::Embed(name, fields) => s.serialize_newtype_variant("BinValue", 24, "embed", &(name, fields)),
+            BinValue::Flag(value) => s.serialize_newtype_variant("BinValue", 25, "flag", value),
+            BinValue::Unknown(type_id, bytes, trailing) => {
+                s.serialize_newtype_variant("BinValue", 26, "unknown", &(type_id, bytes, trailing))
+            }
+            BinValue::Option(value_type, value) => {
+                s.serialize_newtype_variant("BinValue", 27, "option", &(value_type, value))
+            }
+        }
+    }
+}
+
+struct BinValueVisitor;
+
+impl<'de> Visitor<'de> for BinValueVisitor {
+    type Value = BinValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a bin value")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<BinValue, A::Error> {
+        let (index, v) = data.variant_seed(IdentifierSeed(VALUE_VARIANTS))?;
+        Ok(match VALUE_VARIANTS[index] {
+            "none" => {
+                v.unit_variant()?;
+                BinValue::None
+            }
+            "bool" => BinValue::Bool(v.newtype_variant()?),
+            "i8" => BinValue::I8(v.newtype_variant()?),
+            "u8" => BinValue::U8(v.newtype_variant()?),
+            "i16" => BinValue::I16(v.newtype_variant()?),
+            "u16" => BinValue::U16(v.newtype_variant()?),
+            "i32" => BinValue::I32(v.newtype_variant()?),
+            "u32" => BinValue::U32(v.newtype_variant()?),
+            "i64" => BinValue::I64(v.newtype_variant()?),
+            "u64" => BinValue::U64(v.newtype_variant()?),
+            "f32" => BinValue::Float(v.newtype_variant()?),
+            "vec2" => BinValue::Vec2(v.newtype_variant()?),
+            "vec3" => BinValue::Vec3(v.newtype_variant()?),
+            "vec4" => BinValue::Vec4(v.newtype_variant()?),
+            "mtx44" => BinValue::Mtx44(v.newtype_variant()?),
+            "rgba" => BinValue::Rgba(v.newtype_variant()?),
+            "string" => BinValue::String(v.newtype_variant()?),
+            "hash" => BinValue::Hash(v.newtype_variant()?),
+            "link" => BinValue::Link(v.newtype_variant()?),
+            "file" => BinValue::File(v.newtype_variant()?),
+            "list" => {
+                let (value_type, items) = v.newtype_variant()?;
+                BinValue::List(value_type, items)
+            }
+            "list2" => {
+                let (value_type, items) = v.newtype_variant()?;
+                BinValue::List2(value_type, items)
+            }
+            "map" => {
+                let (key_type, value_type, items) = v.newtype_variant()?;
+                BinValue::Map(key_type, value_type, items)
+            }
+            "option" => {
+                let (value_type, value) = v.newtype_variant()?;
+                BinValue::Option(value_type, value)
+            }
+            "pointer" => {
+                let (name, fields) = v.newtype_variant()?;
+                BinValue::Struct(name, fields)
+            }
+            "embed" => {
+                let (name, fields) = v.newtype_variant()?;
+                BinValue::Embed(name, fields)
+            }
+            "flag" => BinValue::Flag(v.newtype_variant()?),
+            _ => {
+                let (type_id, bytes, trailing) = v.newtype_variant()?;
+                BinValue::Unknown(type_id, bytes, trailing)
+            }
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for BinValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum("BinValue", VALUE_VARIANTS, BinValueVisitor)
+    }
+}
+
+impl Serialize for BinPatch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("BinPatch", 3)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("path", &self.path)?;
+        state.serialize_field("value", &self.value)?;
+        state.end()
+    }
+}
+
+struct BinPatchVisitor;
+
+impl<'de> Visitor<'de> for BinPatchVisitor {
+    type Value = BinPatch;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a bin patch")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BinPatch, A::Error> {
+        let missing = |index| de::Error::invalid_length(index, &self);
+        Ok(BinPatch {
+            name: seq.next_element()?.ok_or_else(|| missing(0))?,
+            path: seq.next_element()?.ok_or_else(|| missing(1))?,
+            value: seq.next_element()?.ok_or_else(|| missing(2))?,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<BinPatch, A::Error> {
+        let (mut name, mut path, mut value) = (None, None, None);
+        while let Some(index) = map.next_key_seed(IdentifierSeed(PATCH_FIELDS))? {
+            match index {
+                0 => name = Some(map.next_value()?),
+                1 => path = Some(map.next_value()?),
+                _ => value = Some(map.next_value()?),
+            }
+        }
+        Ok(BinPatch {
+            name: name.ok_or_else(|| de::Error::missing_field("name"))?,
+            path: path.ok_or_else(|| de::Error::missing_field("path"))?,
+            value: value.ok_or_else(|| de::Error::missing_field("value"))?,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for BinPatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("BinPatch", PATCH_FIELDS, BinPatchVisitor)
+    }
+}
+
+impl Serialize for Bin {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Bin", 6)?;
+        state.serialize_field("is_patch", &self.is_patch)?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("links", &self.links)?;
+        state.serialize_field("entries", &self.entries)?;
+        state.serialize_field("patches", &self.patches)?;
+        state.serialize_field("tail", &self.tail)?;
+        state.end()
+    }
+}
+
+struct BinVisitor;
+
+impl<'de> Visitor<'de> for BinVisitor {
+    type Value = Bin;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a bin")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Bin, A::Error> {
+        let missing = |index| de::Error::invalid_length(index, &self);
+        Ok(Bin {
+            is_patch: seq.next_element()?.ok_or_else(|| missing(0))?,
+            version: seq.next_element()?.ok_or_else(|| missing(1))?,
+            links: seq.next_element()?.ok_or_else(|| missing(2))?,
+            entries: seq.next_element()?.ok_or_else(|| missing(3))?,
+            patches: seq.next_element()?.ok_or_else(|| missing(4))?,
+            tail: seq.next_element()?.ok_or_else(|| missing(5))?,
+            errors: Vec::new(),
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Bin, A::Error> {
+        let (mut is_patch, mut version, mut links, mut entries, mut patches, mut tail) =
+            (None, None, None, None, None, None);
+        while let Some(index) = map.next_key_seed(IdentifierSeed(BIN_FIELDS))? {
+            match index {
+                0 => is_patch = Some(map.next_value()?),
+                1 => version = Some(map.next_value()?),
+                2 => links = Some(map.next_value()?),
+                3 => entries = Some(map.next_value()?),
+                4 => patches = Some(map.next_value()?),
+                _ => tail = Some(map.next_value()?),
+            }
+        }
+        Ok(Bin {
+            is_patch: is_patch.unwrap_or(false),
+            version: version.ok_or_else(|| de::Error::missing_field("version"))?,
+            links: links.unwrap_or_default(),
+            entries: entries.ok_or_else(|| de::Error::missing_field("entries"))?,
+            patches: patches.unwrap_or_default(),
+            tail: tail.unwrap_or_default(),
+            errors: Vec::new(),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Bin {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Bin", BIN_FIELDS, BinVisitor)
+    }
+}
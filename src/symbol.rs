@@ -0,0 +1,155 @@
+use crate::*;
+use std::cmp::Ordering;
+#[cfg(feature = "display")]
+use std::fmt::Display;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::{Arc, OnceLock, RwLock};
+
+const SEGMENTS: usize = 33;
+
+type Segment = Box<[OnceLock<Arc<str>>]>;
+
+struct Interner {
+    segments: [OnceLock<Segment>; SEGMENTS],
+    ids: RwLock<HashMap<Arc<str>, u32>>,
+}
+
+fn interner() -> &'static Interner {
+    static INTERNER: OnceLock<Interner> = OnceLock::new();
+    INTERNER.get_or_init(|| Interner {
+        segments: [const { OnceLock::new() }; SEGMENTS],
+        ids: RwLock::new(HashMap::new()),
+    })
+}
+
+fn slot(id: u32) -> (usize, usize) {
+    let position = id as u64 + 1;
+    let segment = 63 - position.leading_zeros() as usize;
+    (segment, (position - (1 << segment)) as usize)
+}
+
+fn intern_with<F: FnOnce() -> Arc<str>>(string: &str, shared: F) -> u32 {
+    if string.is_empty() {
+        return 0;
+    }
+    let interner = interner();
+    if let Some(id) = interner.ids.read().unwrap().get(string) {
+        return *id;
+    }
+    let mut ids = interner.ids.write().unwrap();
+    if let Some(id) = ids.get(string) {
+        return *id;
+    }
+    let id = ids.len() as u32 + 1;
+    let (segment, index) = slot(id);
+    let string = shared();
+    interner.segments[segment]
+        .get_or_init(|| (0..1usize << segment).map(|_| OnceLock::new()).collect())[index]
+        .get_or_init(|| string.clone());
+    ids.insert(string, id);
+    id
+}
+
+fn resolve(id: u32) -> &'static str {
+    if id == 0 {
+        return "";
+    }
+    let (segment, index) = slot(id);
+    interner().segments[segment]
+        .get()
+        .and_then(|strings| strings[index].get())
+        .map_or("", |string| string)
+}
+
+#[derive(Clone, Copy)]
+pub struct Name {
+    hash: u32,
+    id: u32,
+}
+
+impl Name {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl BinHashed for Name {
+    type HashType = u32;
+
+    fn from_hash(hash: Self::HashType) -> Self {
+        Self { hash, id: 0 }
+    }
+
+    fn from_string(string: &str) -> Self {
+        Self {
+            hash: fnv(string),
+            id: 0,
+        }
+    }
+
+    fn from_hash_string(hash: Self::HashType, string: &str) -> Self {
+        Self {
+            hash,
+            id: intern_with(string, || Arc::from(string)),
+        }
+    }
+
+    fn from_hash_shared(hash: Self::HashType, string: Arc<str>) -> Self {
+        Self {
+            hash,
+            id: intern_with(&string, || string.clone()),
+        }
+    }
+
+    fn get_hash(&self) -> Self::HashType {
+        self.hash
+    }
+
+    fn get_string(&self) -> &str {
+        resolve(self.id)
+    }
+
+    fn format_to(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        BinHashDisplay::current().write_name(f, resolve(self.id), self.hash as u64, 8, true)
+    }
+}
+
+#[cfg(feature = "display")]
+impl Display for Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.format_to(f)
+    }
+}
+
+impl Debug for Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.format_to(f)
+    }
+}
+
+impl Hash for Name {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hash.hash(state)
+    }
+}
+
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl Eq for Name {}
+
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Name {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.hash.cmp(&other.hash)
+    }
+}
@@ -0,0 +1,774 @@
+use crate::*;
+use std::fmt::Write;
+
+type Result<T> = std::result::Result<T, BinError>;
+
+fn value_type_name(value: &BinValue) -> String {
+    match value {
+        BinValue::None => "none".to_string(),
+        BinValue::Bool(_) => "bool".to_string(),
+        BinValue::I8(_) => "i8".to_string(),
+        BinValue::U8(_) => "u8".to_string(),
+        BinValue::I16(_) => "i16".to_string(),
+        BinValue::U16(_) => "u16".to_string(),
+        BinValue::I32(_) => "i32".to_string(),
+        BinValue::U32(_) => "u32".to_string(),
+        BinValue::I64(_) => "i64".to_string(),
+        BinValue::U64(_) => "u64".to_string(),
+        BinValue::Float(_) => "f32".to_string(),
+        BinValue::Vec2(_) => "vec2".to_string(),
+        BinValue::Vec3(_) => "vec3".to_string(),
+        BinValue::Vec4(_) => "vec4".to_string(),
+        BinValue::Mtx44(_) => "mtx44".to_string(),
+        BinValue::Rgba(_) => "rgba".to_string(),
+        BinValue::String(_) => "string".to_string(),
+        BinValue::Hash(_) => "hash".to_string(),
+        BinValue::Link(_) => "link".to_string(),
+        BinValue::File(_) => "file".to_string(),
+        BinValue::List(value_type, items) => format!("list[{}]", items_type_name(*value_type, items.iter())),
+        BinValue::List2(value_type, items) => format!("list2[{}]", items_type_name(*value_type, items.iter())),
+        BinValue::Option(value_type, value) => {
+            format!("option[{}]", items_type_name(*value_type, value.as_deref().into_iter()))
+        }
+        BinValue::Map(key_type, value_type, items) => format!(
+            "map[{},{}]",
+            items_type_name(*key_type, items.iter().map(|(key, _)| key)),
+            items_type_name(*value_type, items.iter().map(|(_, value)| value))
+        ),
+        BinValue::Struct(_, _) => "pointer".to_string(),
+        BinValue::Embed(_, _) => "embed".to_string(),
+        BinValue::Flag(_) => "flag".to_string(),
+        BinValue::Unknown(_, _, _) => "unknown".to_string(),
+    }
+}
+
+fn items_type_name<'v, I>(declared: BinType, items: I) -> String
+where
+    I: Iterator<Item = &'v BinValue>,
+{
+    if declared != BinType::None {
+        return declared.name().to_string();
+    }
+    let mut result = "none";
+    for item in items {
+        match item {
+            BinValue::None => result = "pointer",
+            BinValue::Unknown(_, _, _) => {}
+            _ => return value_type_name(item),
+        }
+    }
+    result.to_string()
+}
+
+fn escape_string(value: &str, out: &mut String) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct TextWriter<'a> {
+    out: String,
+    indent: usize,
+    hashes: &'a BinHashes,
+}
+
+impl<'a> TextWriter<'a> {
+    fn write_indent(&mut self) {
+        for _ in 0..self.indent {
+            self.out.push_str("    ");
+        }
+    }
+
+    fn write_fnv(&mut self, name: &BinFNV, list: &BinHashList<BinFNV>) {
+        let name = if name.get_string().is_empty() {
+            list.get(name.get_hash())
+        } else {
+            *name
+        };
+        if name.get_string().is_empty() {
+            write!(self.out, "0x{:08x}", name.get_hash()).unwrap();
+        } else {
+            escape_string(name.get_string(), &mut self.out);
+        }
+    }
+
+    fn write_xxh(&mut self, name: &BinXXH) {
+        let name = if name.get_string().is_empty() {
+            self.hashes.paths.get(name.get_hash())
+        } else {
+            name.clone()
+        };
+        if name.get_string().is_empty() {
+            write!(self.out, "0x{:016x}", name.get_hash()).unwrap();
+        } else {
+            escape_string(name.get_string(), &mut self.out);
+        }
+    }
+
+    fn write_type_name(&mut self, name: &BinFNV) {
+        let name = if name.get_string().is_empty() {
+            self.hashes.types.get(name.get_hash())
+        } else {
+            *name
+        };
+        if name.get_string().is_empty() {
+            write!(self.out, "0x{:08x}", name.get_hash()).unwrap();
+        } else {
+            self.out.push_str(name.get_string());
+        }
+    }
+
+    fn write_field_name(&mut self, name: &BinFNV) {
+        let name = if name.get_string().is_empty() {
+            self.hashes.fields.get(name.get_hash())
+        } else {
+            *name
+        };
+        if name.get_string().is_empty() {
+            write!(self.out, "0x{:08x}", name.get_hash()).unwrap();
+        } else {
+            self.out.push_str(name.get_string());
+        }
+    }
+
+    fn write_floats(&mut self, values: &[f32]) {
+        let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        write!(self.out, "{{ {} }}", values.join(", ")).unwrap();
+    }
+
+    fn write_fields(&mut self, fields: &IndexMap<BinFNV, BinValue>) {
+        for (name, value) in fields {
+            self.write_indent();
+            self.write_field_name(name);
+            write!(self.out, ": {} = ", value_type_name(value)).unwrap();
+            self.write_value(value);
+            self.out.push('\n');
+        }
+    }
+
+    fn write_items<'v, I>(&mut self, items: I)
+    where
+        I: ExactSizeIterator<Item = &'v BinValue>,
+    {
+        if items.len() == 0 {
+            self.out.push_str("{}");
+            return;
+        }
+        self.out.push_str("{\n");
+        self.indent += 1;
+        for item in items {
+            self.write_indent();
+            self.write_value(item);
+            self.out.push('\n');
+        }
+        self.indent -= 1;
+        self.write_indent();
+        self.out.push('}');
+    }
+
+    fn write_value(&mut self, value: &BinValue) {
+        match value {
+            BinValue::None => self.out.push_str("null"),
+            BinValue::Struct(_, _) | BinValue::Embed(_, _) if value.is_null() => self.out.push_str("null"),
+            BinValue::Bool(value) | BinValue::Flag(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::I8(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::U8(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::I16(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::U16(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::I32(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::U32(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::I64(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::U64(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::Float(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::Vec2(value) => self.write_floats(value),
+            BinValue::Vec3(value) => self.write_floats(value),
+            BinValue::Vec4(value) => self.write_floats(value),
+            BinValue::Mtx44(value) => {
+                self.out.push_str("{\n");
+                self.indent += 1;
+                for row in value {
+                    self.write_indent();
+                    let row: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+                    writeln!(self.out, "{}", row.join(", ")).unwrap();
+                }
+                self.indent -= 1;
+                self.write_indent();
+                self.out.push('}');
+            }
+            BinValue::Rgba(value) => {
+                write!(self.out, "{{ {}, {}, {}, {} }}", value[0], value[1], value[2], value[3]).unwrap()
+            }
+            BinValue::String(value) => escape_string(value, &mut self.out),
+            BinValue::Hash(value) => self.write_fnv(value, &self.hashes.hashes),
+            BinValue::Link(value) => self.write_fnv(value, &self.hashes.entries),
+            BinValue::File(value) => self.write_xxh(value),
+            BinValue::List(_, items) | BinValue::List2(_, items) => self.write_items(items.iter()),
+            BinValue::Option(_, value) => self.write_items(value.as_deref().into_iter()),
+            BinValue::Map(_, _, items) => {
+                if items.is_empty() {
+                    self.out.push_str("{}");
+                    return;
+                }
+                self.out.push_str("{\n");
+                self.indent += 1;
+                for (key, value) in items {
+                    self.write_indent();
+                    self.write_value(key);
+                    self.out.push_str(" = ");
+                    self.write_value(value);
+                    self.out.push('\n');
+                }
+                self.indent -= 1;
+                self.write_indent();
+                self.out.push('}');
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                self.write_type_name(name);
+                if fields.is_empty() {
+                    self.out.push_str(" {}");
+                    return;
+                }
+                self.out.push_str(" {\n");
+                self.indent += 1;
+                self.write_fields(fields);
+                self.indent -= 1;
+                self.write_indent();
+                self.out.push('}');
+            }
+            BinValue::Unknown(type_id, bytes, trailing) => {
+                write!(self.out, "0x{:02X} ", type_id).unwrap();
+                if *trailing != 0 {
+                    write!(self.out, "{} ", trailing).unwrap();
+                }
+                self.out.push('"');
+                for byte in bytes {
+                    write!(self.out, "{:02x}", byte).unwrap();
+                }
+                self.out.push('"');
+            }
+        }
+    }
+
+    fn write_bin(&mut self, bin: &Bin) {
+        self.out.push_str("#PROP_text\n");
+        let magic = if bin.is_patch { "PTCH" } else { "PROP" };
+        writeln!(self.out, "type: string = \"{}\"", magic).unwrap();
+        writeln!(self.out, "version: u32 = {}", bin.version).unwrap();
+        self.out.push_str("linked: list[string] = ");
+        let links: Vec<BinValue> = bin.links.iter().cloned().map(BinValue::String).collect();
+        self.write_items(links.iter());
+        self.out.push('\n');
+        self.out.push_str("entries: map[hash,embed] = {\n");
+        self.indent += 1;
+        for (name, value) in &bin.entries {
+            self.write_indent();
+            self.write_fnv(name, &self.hashes.entries);
+            self.out.push_str(" = ");
+            self.write_value(value);
+            self.out.push('\n');
+        }
+        self.indent -= 1;
+        self.out.push_str("}\n");
+        if bin.is_patch {
+            self.out.push_str("patches: map[hash,embed] = {\n");
+            self.indent += 1;
+            for patch in &bin.patches {
+                self.write_indent();
+                self.write_fnv(&patch.name, &self.hashes.entries);
+                self.out.push_str(" = patch {\n");
+                self.indent += 1;
+                self.write_indent();
+                self.out.push_str("path: string = ");
+                escape_string(&patch.path, &mut self.out);
+                self.out.push('\n');
+                self.write_indent();
+                write!(self.out, "value: {} = ", value_type_name(&patch.value)).unwrap();
+                self.write_value(&patch.value);
+                self.out.push('\n');
+                self.indent -= 1;
+                self.write_indent();
+                self.out.push_str("}\n");
+            }
+            self.indent -= 1;
+            self.out.push_str("}\n");
+        }
+    }
+}
+
+impl Bin {
+    pub fn to_text(&self, hashes: &BinHashes) -> String {
+        let mut writer = TextWriter {
+            out: String::new(),
+            indent: 0,
+            hashes,
+        };
+        writer.write_bin(self);
+        writer.out
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Punct(char),
+    Word(String),
+    Quoted(String),
+}
+
+enum TextType {
+    Simple(String),
+    List(bool, Box<TextType>),
+    Option(Box<TextType>),
+    Map(Box<TextType>, Box<TextType>),
+}
+
+impl TextType {
+    fn bin_type(&self) -> BinType {
+        match self {
+            TextType::Simple(name) => BinType::from_name(name).unwrap_or(BinType::None),
+            TextType::List(false, _) => BinType::List,
+            TextType::List(true, _) => BinType::List2,
+            TextType::Option(_) => BinType::Option,
+            TextType::Map(_, _) => BinType::Map,
+        }
+    }
+}
+
+fn text_error(line: usize, message: String) -> BinError {
+    BinError::Text { line, message }
+}
+
+fn tokenize(data: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '#' => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '{' | '}' | '[' | ']' | '=' | ':' | ',' => tokens.push((Token::Punct(c), line)),
+            '"' | '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('r') => value.push('\r'),
+                            Some('t') => value.push('\t'),
+                            Some(escaped) => value.push(escaped),
+                            None => return Err(text_error(line, "Unterminated string".to_string())),
+                        },
+                        Some('\n') | None => return Err(text_error(line, "Unterminated string".to_string())),
+                        Some(c) => value.push(c),
+                    }
+                }
+                tokens.push((Token::Quoted(value), line));
+            }
+            _ if c.is_whitespace() => {}
+            _ => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "{}[]=:,\"'#".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push((Token::Word(word), line));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_hex(string: &str) -> Option<u64> {
+    u64::from_str_radix(string.strip_prefix("0x")?, 16).ok()
+}
+
+struct TextParser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl TextParser {
+    fn line(&self) -> usize {
+        match self.tokens.get(self.pos).or(self.tokens.last()) {
+            Some((_, line)) => *line,
+            None => 1,
+        }
+    }
+
+    fn error(&self, message: String) -> BinError {
+        text_error(self.line(), message)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        match self.tokens.get(self.pos) {
+            Some((token, _)) => {
+                self.pos += 1;
+                Ok(token.clone())
+            }
+            None => Err(self.error("Unexpected end of input".to_string())),
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        match self.next()? {
+            Token::Punct(p) if p == c => Ok(()),
+            token => Err(self.error(format!("Expected {:?}, found {:?}", c, token))),
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn word(&mut self) -> Result<String> {
+        match self.next()? {
+            Token::Word(word) => Ok(word),
+            token => Err(self.error(format!("Expected word, found {:?}", token))),
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<TextType> {
+        let name = self.word()?;
+        match name.as_str() {
+            "list" | "list2" => {
+                self.expect('[')?;
+                let item = self.parse_type()?;
+                self.expect(']')?;
+                Ok(TextType::List(name == "list2", Box::new(item)))
+            }
+            "option" => {
+                self.expect('[')?;
+                let item = self.parse_type()?;
+                self.expect(']')?;
+                Ok(TextType::Option(Box::new(item)))
+            }
+            "map" => {
+                self.expect('[')?;
+                let key = self.parse_type()?;
+                self.expect(',')?;
+                let value = self.parse_type()?;
+                self.expect(']')?;
+                Ok(TextType::Map(Box::new(key), Box::new(value)))
+            }
+            _ => Ok(TextType::Simple(name)),
+        }
+    }
+
+    fn parse_number<T: std::str::FromStr>(&mut self) -> Result<T> {
+        let word = self.word()?;
+        word.parse()
+            .map_err(|_| self.error(format!("Bad number {:?}", word)))
+    }
+
+    fn parse_bool(&mut self) -> Result<bool> {
+        match self.word()?.as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            word => Err(self.error(format!("Bad bool {:?}", word))),
+        }
+    }
+
+    fn parse_numbers<T: std::str::FromStr>(&mut self, count: usize) -> Result<Vec<T>> {
+        self.expect('{')?;
+        let mut result = Vec::new();
+        while !self.eat('}') {
+            result.push(self.parse_number()?);
+            self.eat(',');
+        }
+        if result.len() != count {
+            return Err(self.error(format!("Expected {} numbers, found {}", count, result.len())));
+        }
+        Ok(result)
+    }
+
+    fn parse_fnv(&mut self) -> Result<BinFNV> {
+        let (string, quoted) = match self.next()? {
+            Token::Word(word) => (word, false),
+            Token::Quoted(string) => (string, true),
+            token => return Err(self.error(format!("Expected hash, found {:?}", token))),
+        };
+        match parse_hex(&string) {
+            Some(hash) if !quoted && hash <= u32::MAX as u64 => Ok(BinFNV::from_hash(hash as u32)),
+            Some(_) if !quoted => Err(self.error(format!("Hash {} out of range", string))),
+            _ => Ok(BinFNV::from_hash_string(fnv(&string), &string)),
+        }
+    }
+
+    fn parse_xxh(&mut self) -> Result<BinXXH> {
+        match self.next()? {
+            Token::Word(word) => match parse_hex(&word) {
+                Some(hash) => Ok(BinXXH::from_hash(hash)),
+                None => Err(self.error(format!("Bad file hash {:?}", word))),
+            },
+            Token::Quoted(string) => Ok(BinXXH::from_hash_string(xxh64(&string), &string)),
+            token => Err(self.error(format!("Expected file, found {:?}", token))),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        match self.next()? {
+            Token::Quoted(string) => Ok(string),
+            token => Err(self.error(format!("Expected string, found {:?}", token))),
+        }
+    }
+
+    fn parse_fields(&mut self) -> Result<IndexMap<BinFNV, BinValue>> {
+        self.expect('{')?;
+        let mut fields = IndexMap::new();
+        while !self.eat('}') {
+            let name = self.parse_fnv()?;
+            self.expect(':')?;
+            let field_type = self.parse_type()?;
+            self.expect('=')?;
+            let value = self.parse_value(&field_type)?;
+            fields.insert(name, value);
+            self.eat(',');
+        }
+        Ok(fields)
+    }
+
+    fn parse_value(&mut self, value_type: &TextType) -> Result<BinValue> {
+        let name = match value_type {
+            TextType::List(list2, item_type) => {
+                self.expect('{')?;
+                let mut items = Vec::new();
+                while !self.eat('}') {
+                    items.push(self.parse_value(item_type)?);
+                    self.eat(',');
+                }
+                return Ok(if *list2 {
+                    BinValue::List2(item_type.bin_type(), items)
+                } else {
+                    BinValue::List(item_type.bin_type(), items)
+                });
+            }
+            TextType::Option(item_type) => {
+                self.expect('{')?;
+                let mut value = None;
+                if !self.eat('}') {
+                    value = Some(Box::new(self.parse_value(item_type)?));
+                    self.eat(',');
+                    self.expect('}')?;
+                }
+                return Ok(BinValue::Option(item_type.bin_type(), value));
+            }
+            TextType::Map(key_type, value_type) => {
+                self.expect('{')?;
+                let mut items = Vec::new();
+                while !self.eat('}') {
+                    let key = self.parse_value(key_type)?;
+                    self.expect('=')?;
+                    let value = self.parse_value(value_type)?;
+                    items.push((key, value));
+                    self.eat(',');
+                }
+                return Ok(BinValue::Map(key_type.bin_type(), value_type.bin_type(), items));
+            }
+            TextType::Simple(name) => name,
+        };
+        Ok(match name.as_str() {
+            "none" => match self.word()?.as_str() {
+                "null" => BinValue::None,
+                word => return Err(self.error(format!("Expected null, found {:?}", word))),
+            },
+            "bool" => BinValue::Bool(self.parse_bool()?),
+            "flag" => BinValue::Flag(self.parse_bool()?),
+            "i8" => BinValue::I8(self.parse_number()?),
+            "u8" => BinValue::U8(self.parse_number()?),
+            "i16" => BinValue::I16(self.parse_number()?),
+            "u16" => BinValue::U16(self.parse_number()?),
+            "i32" => BinValue::I32(self.parse_number()?),
+            "u32" => BinValue::U32(self.parse_number()?),
+            "i64" => BinValue::I64(self.parse_number()?),
+            "u64" => BinValue::U64(self.parse_number()?),
+            "f32" => BinValue::Float(self.parse_number()?),
+            "vec2" => {
+                let values = self.parse_numbers(2)?;
+                BinValue::Vec2([values[0], values[1]])
+            }
+            "vec3" => {
+                let values = self.parse_numbers(3)?;
+                BinValue::Vec3([values[0], values[1], values[2]])
+            }
+            "vec4" => {
+                let values = self.parse_numbers(4)?;
+                BinValue::Vec4([values[0], values[1], values[2], values[3]])
+            }
+            "mtx44" => {
+                let values: Vec<f32> = self.parse_numbers(16)?;
+                let mut result = [[0.0; 4]; 4];
+                for (index, value) in values.into_iter().enumerate() {
+                    result[index / 4][index % 4] = value;
+                }
+                BinValue::Mtx44(result)
+            }
+            "rgba" => {
+                let values = self.parse_numbers(4)?;
+                BinValue::Rgba([values[0], values[1], values[2], values[3]])
+            }
+            "string" => BinValue::String(self.parse_string()?),
+            "hash" => BinValue::Hash(self.parse_fnv()?),
+            "link" => BinValue::Link(self.parse_fnv()?),
+            "file" => BinValue::File(self.parse_xxh()?),
+            "pointer" | "embed" => {
+                let (class, fields) = if self.peek() == Some(&Token::Word("null".to_string())) {
+                    self.pos += 1;
+                    (BinFNV::from_hash(0), IndexMap::new())
+                } else {
+                    (self.parse_fnv()?, self.parse_fields()?)
+                };
+                if name == "pointer" {
+                    BinValue::Struct(class, fields)
+                } else {
+                    BinValue::Embed(class, fields)
+                }
+            }
+            "unknown" => self.parse_unknown()?,
+            _ => return Err(self.error(format!("Unknown value type {:?}", name))),
+        })
+    }
+
+    fn parse_unknown(&mut self) -> Result<BinValue> {
+        let word = self.word()?;
+        let type_id = match parse_hex(&word) {
+            Some(type_id) if type_id <= u8::MAX as u64 => type_id as u8,
+            _ => return Err(self.error(format!("Bad unknown type id {:?}", word))),
+        };
+        let trailing = match self.peek() {
+            Some(Token::Word(_)) => self.parse_number()?,
+            _ => 0,
+        };
+        let hex = self.parse_string()?;
+        if hex.len() % 2 != 0 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(self.error(format!("Bad unknown bytes {:?}", hex)));
+        }
+        let mut bytes = Vec::new();
+        for index in (0..hex.len()).step_by(2) {
+            match u8::from_str_radix(&hex[index..index + 2], 16) {
+                Ok(byte) => bytes.push(byte),
+                Err(_) => return Err(self.error(format!("Bad unknown bytes {:?}", hex))),
+            }
+        }
+        Ok(BinValue::Unknown(type_id, bytes, trailing))
+    }
+
+    fn parse_patch(&mut self) -> Result<(String, BinValue)> {
+        match self.word()?.as_str() {
+            "patch" => {}
+            word => return Err(self.error(format!("Expected patch, found {:?}", word))),
+        }
+        let mut path = None;
+        let mut value = None;
+        self.expect('{')?;
+        while !self.eat('}') {
+            let name = self.word()?;
+            self.expect(':')?;
+            let value_type = self.parse_type()?;
+            self.expect('=')?;
+            match name.as_str() {
+                "path" => path = Some(self.parse_string()?),
+                "value" => value = Some(self.parse_value(&value_type)?),
+                _ => return Err(self.error(format!("Unknown patch field {:?}", name))),
+            }
+        }
+        match (path, value) {
+            (Some(path), Some(value)) => Ok((path, value)),
+            _ => Err(self.error("Patch needs path and value".to_string())),
+        }
+    }
+
+    fn parse_bin(&mut self) -> Result<Bin> {
+        let mut bin = Bin {
+            is_patch: false,
+            version: 3,
+            links: Vec::new(),
+            entries: IndexMap::new(),
+            patches: Vec::new(),
+            tail: Vec::new(),
+            errors: Vec::new(),
+        };
+        while self.peek().is_some() {
+            let section = self.word()?;
+            self.expect(':')?;
+            let section_type = self.parse_type()?;
+            self.expect('=')?;
+            match section.as_str() {
+                "type" => {
+                    bin.is_patch = match self.parse_string()?.as_str() {
+                        "PROP" => false,
+                        "PTCH" => true,
+                        magic => return Err(self.error(format!("Bad bin type {:?}", magic))),
+                    }
+                }
+                "version" => bin.version = self.parse_number()?,
+                "linked" => {
+                    self.expect('{')?;
+                    while !self.eat('}') {
+                        bin.links.push(self.parse_string()?);
+                        self.eat(',');
+                    }
+                }
+                "entries" => {
+                    self.expect('{')?;
+                    while !self.eat('}') {
+                        let name = self.parse_fnv()?;
+                        self.expect('=')?;
+                        let value = self.parse_value(&TextType::Simple("embed".to_string()))?;
+                        if !matches!(value, BinValue::Embed(_, _)) {
+                            return Err(self.error("Entry must be an embed".to_string()));
+                        }
+                        bin.entries.insert(name, value);
+                    }
+                }
+                "patches" => {
+                    self.expect('{')?;
+                    while !self.eat('}') {
+                        let name = self.parse_fnv()?;
+                        self.expect('=')?;
+                        let (path, value) = self.parse_patch()?;
+                        bin.patches.push(BinPatch { name, path, value });
+                    }
+                }
+                _ => {
+                    self.parse_value(&section_type)?;
+                }
+            }
+        }
+        Ok(bin)
+    }
+}
+
+impl Bin {
+    pub fn read_from_text(data: &str) -> Result<Bin> {
+        let mut parser = TextParser {
+            tokens: tokenize(data)?,
+            pos: 0,
+        };
+        parser.parse_bin()
+    }
+}
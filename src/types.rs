@@ -0,0 +1,177 @@
+use crate::*;
+use num_enum::TryFromPrimitive;
+use std::convert::TryFrom;
+#[cfg(feature = "display")]
+use std::fmt::Display;
+
+#[derive(TryFromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+pub enum BinType {
+    None = 0,
+    Bool = 1,
+    I8 = 2,
+    U8 = 3,
+    I16 = 4,
+    U16 = 5,
+    I32 = 6,
+    U32 = 7,
+    I64 = 8,
+    U64 = 9,
+    F32 = 10,
+    Vec2 = 11,
+    Vec3 = 12,
+    Vec4 = 13,
+    Mtx44 = 14,
+    Rgba = 15,
+    String = 16,
+    Hash = 17,
+    File = 18,
+    List = 0x80,
+    List2 = 0x80 | 1,
+    Pointer = 0x80 | 2,
+    Embed = 0x80 | 3,
+    Link = 0x80 | 4,
+    Option = 0x80 | 5,
+    Map = 0x80 | 6,
+    Flag = 0x80 | 7,
+}
+
+impl BinType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BinType::None => "none",
+            BinType::Bool => "bool",
+            BinType::I8 => "i8",
+            BinType::U8 => "u8",
+            BinType::I16 => "i16",
+            BinType::U16 => "u16",
+            BinType::I32 => "i32",
+            BinType::U32 => "u32",
+            BinType::I64 => "i64",
+            BinType::U64 => "u64",
+            BinType::F32 => "f32",
+            BinType::Vec2 => "vec2",
+            BinType::Vec3 => "vec3",
+            BinType::Vec4 => "vec4",
+            BinType::Mtx44 => "mtx44",
+            BinType::Rgba => "rgba",
+            BinType::String => "string",
+            BinType::Hash => "hash",
+            BinType::File => "file",
+            BinType::List => "list",
+            BinType::List2 => "list2",
+            BinType::Pointer => "pointer",
+            BinType::Embed => "embed",
+            BinType::Link => "link",
+            BinType::Option => "option",
+            BinType::Map => "map",
+            BinType::Flag => "flag",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BinType> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" => Some(BinType::None),
+            "bool" => Some(BinType::Bool),
+            "i8" => Some(BinType::I8),
+            "u8" => Some(BinType::U8),
+            "i16" => Some(BinType::I16),
+            "u16" => Some(BinType::U16),
+            "i32" => Some(BinType::I32),
+            "u32" => Some(BinType::U32),
+            "i64" => Some(BinType::I64),
+            "u64" => Some(BinType::U64),
+            "f32" => Some(BinType::F32),
+            "vec2" => Some(BinType::Vec2),
+            "vec3" => Some(BinType::Vec3),
+            "vec4" => Some(BinType::Vec4),
+            "mtx44" => Some(BinType::Mtx44),
+            "rgba" => Some(BinType::Rgba),
+            "string" => Some(BinType::String),
+            "hash" => Some(BinType::Hash),
+            "file" => Some(BinType::File),
+            "list" => Some(BinType::List),
+            "list2" => Some(BinType::List2),
+            "pointer" => Some(BinType::Pointer),
+            "embed" => Some(BinType::Embed),
+            "link" => Some(BinType::Link),
+            "option" => Some(BinType::Option),
+            "map" => Some(BinType::Map),
+            "flag" => Some(BinType::Flag),
+            _ => None,
+        }
+    }
+
+    pub fn is_container(&self) -> bool {
+        matches!(self, BinType::List | BinType::List2 | BinType::Option | BinType::Map)
+    }
+
+    pub fn is_struct(&self) -> bool {
+        matches!(self, BinType::Pointer | BinType::Embed)
+    }
+
+    pub(crate) fn of_items<'v, I>(items: I) -> BinType
+    where
+        I: Iterator<Item = &'v BinValue>,
+    {
+        let mut result = BinType::None;
+        for item in items {
+            match item {
+                BinValue::None => result = BinType::Pointer,
+                BinValue::Unknown(_, _, _) => {}
+                _ => return item.bin_type(),
+            }
+        }
+        result
+    }
+}
+
+#[cfg(feature = "display")]
+impl Display for BinType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl BinValue {
+    pub fn is_null(&self) -> bool {
+        match self {
+            BinValue::None => true,
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => name.get_hash() == 0 && fields.is_empty(),
+            _ => false,
+        }
+    }
+
+    pub fn bin_type(&self) -> BinType {
+        match self {
+            BinValue::None => BinType::None,
+            BinValue::Bool(_) => BinType::Bool,
+            BinValue::I8(_) => BinType::I8,
+            BinValue::U8(_) => BinType::U8,
+            BinValue::I16(_) => BinType::I16,
+            BinValue::U16(_) => BinType::U16,
+            BinValue::I32(_) => BinType::I32,
+            BinValue::U32(_) => BinType::U32,
+            BinValue::I64(_) => BinType::I64,
+            BinValue::U64(_) => BinType::U64,
+            BinValue::Float(_) => BinType::F32,
+            BinValue::Vec2(_) => BinType::Vec2,
+            BinValue::Vec3(_) => BinType::Vec3,
+            BinValue::Vec4(_) => BinType::Vec4,
+            BinValue::Mtx44(_) => BinType::Mtx44,
+            BinValue::Rgba(_) => BinType::Rgba,
+            BinValue::String(_) => BinType::String,
+            BinValue::Hash(_) => BinType::Hash,
+            BinValue::Link(_) => BinType::Link,
+            BinValue::File(_) => BinType::File,
+            BinValue::List(_, _) => BinType::List,
+            BinValue::List2(_, _) => BinType::List2,
+            BinValue::Option(_, _) => BinType::Option,
+            BinValue::Map(_, _, _) => BinType::Map,
+            BinValue::Struct(_, _) => BinType::Pointer,
+            BinValue::Embed(_, _) => BinType::Embed,
+            BinValue::Flag(_) => BinType::Flag,
+            BinValue::Unknown(type_id, _, _) => BinType::try_from(*type_id).unwrap_or(BinType::None),
+        }
+    }
+}
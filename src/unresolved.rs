@@ -0,0 +1,90 @@
+use crate::*;
+use std::collections::BTreeSet;
+
+#[derive(Clone, Debug, Default)]
+pub struct BinUnresolved {
+    pub entries: BTreeSet<u32>,
+    pub fields: BTreeSet<u32>,
+    pub types: BTreeSet<u32>,
+    pub hashes: BTreeSet<u32>,
+    pub paths: BTreeSet<u64>,
+}
+
+fn add_name(set: &mut BTreeSet<u32>, name: &BinFNV) {
+    if name.get_string().is_empty() && name.get_hash() != 0 {
+        set.insert(name.get_hash());
+    }
+}
+
+impl BinUnresolved {
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.fields.len() + self.types.len() + self.hashes.len() + self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn merge(&mut self, other: &BinUnresolved) {
+        self.entries.extend(&other.entries);
+        self.fields.extend(&other.fields);
+        self.types.extend(&other.types);
+        self.hashes.extend(&other.hashes);
+        self.paths.extend(&other.paths);
+    }
+
+    pub fn summary(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("entry", self.entries.len()),
+            ("field", self.fields.len()),
+            ("type", self.types.len()),
+            ("hash", self.hashes.len()),
+            ("path", self.paths.len()),
+        ]
+    }
+
+    fn add_value(&mut self, value: &BinValue) {
+        match value {
+            BinValue::Hash(name) => add_name(&mut self.hashes, name),
+            BinValue::Link(name) => add_name(&mut self.entries, name),
+            BinValue::File(file) if file.get_string().is_empty() => {
+                self.paths.insert(file.get_hash());
+            }
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                for item in items {
+                    self.add_value(item);
+                }
+            }
+            BinValue::Option(_, Some(item)) => self.add_value(item),
+            BinValue::Map(_, _, items) => {
+                for (key, value) in items {
+                    self.add_value(key);
+                    self.add_value(value);
+                }
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                add_name(&mut self.types, name);
+                for (name, value) in fields {
+                    add_name(&mut self.fields, name);
+                    self.add_value(value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Bin {
+    pub fn unresolved_hashes(&self) -> BinUnresolved {
+        let mut result = BinUnresolved::default();
+        for (name, value) in &self.entries {
+            add_name(&mut result.entries, name);
+            result.add_value(value);
+        }
+        for patch in &self.patches {
+            add_name(&mut result.entries, &patch.name);
+            result.add_value(&patch.value);
+        }
+        result
+    }
+}
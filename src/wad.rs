@@ -0,0 +1,166 @@
+use crate::*;
+use flate2::read::GzDecoder;
+use num_enum::TryFromPrimitive;
+use std::convert::{TryFrom, TryInto};
+
+type Result<T> = std::result::Result<T, BinError>;
+
+#[derive(TryFromPrimitive, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum BinWadCompression {
+    None = 0,
+    Gzip = 1,
+    Satellite = 2,
+    Zstd = 3,
+    ZstdMulti = 4,
+}
+
+impl BinWadCompression {
+    pub fn is_supported(self) -> bool {
+        matches!(self, BinWadCompression::None | BinWadCompression::Gzip)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BinWadChunk {
+    pub path: BinXXH,
+    pub offset: u32,
+    pub compressed_size: u32,
+    pub size: u32,
+    pub compression: u8,
+    pub subchunk_count: u8,
+    pub duplicate: bool,
+    pub subchunk_index: u16,
+    pub checksum: u64,
+}
+
+impl BinWadChunk {
+    pub fn compression(&self) -> Option<BinWadCompression> {
+        BinWadCompression::try_from(self.compression).ok()
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.compression().is_some_and(BinWadCompression::is_supported)
+    }
+
+    pub fn is_bin(&self) -> bool {
+        self.path.get_string().to_ascii_lowercase().ends_with(".bin")
+    }
+}
+
+fn read_array<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(BinError::UnexpectedEof { offset: offset as u64 })
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    read_array(data, offset).map(u16::from_le_bytes)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    read_array(data, offset).map(u32::from_le_bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    read_array(data, offset).map(u64::from_le_bytes)
+}
+
+pub struct BinWad {
+    pub major: u8,
+    pub minor: u8,
+    pub chunks: Vec<BinWadChunk>,
+    data: Vec<u8>,
+}
+
+impl BinWad {
+    pub fn read_from_data(data: Vec<u8>, hashes: &BinHashes) -> Result<BinWad> {
+        let magic = read_array::<4>(&data, 0)?;
+        if &magic[..2] != b"RW" {
+            return Err(BinError::BadMagic {
+                offset: 0,
+                magic: u32::from_le_bytes(magic),
+            });
+        }
+        let (major, minor) = (magic[2], magic[3]);
+        let (toc_offset, entry_size, count) = match major {
+            1 => (read_u16(&data, 4)? as usize, read_u16(&data, 6)? as usize, read_u32(&data, 8)?),
+            2 => (read_u16(&data, 96)? as usize, read_u16(&data, 98)? as usize, read_u32(&data, 100)?),
+            3 => (272, 32, read_u32(&data, 268)?),
+            _ => return Err(BinError::UnsupportedWad { major, minor }),
+        };
+        let mut chunks = Vec::new();
+        for index in 0..count as usize {
+            let offset = toc_offset + index * entry_size;
+            let kind = read_array::<1>(&data, offset + 20)?[0];
+            let (compression, subchunk_count) = match (major, minor) {
+                (3, 1..) => (kind & 0xF, kind >> 4),
+                _ => (kind, 0),
+            };
+            chunks.push(BinWadChunk {
+                path: hashes.paths.get(read_u64(&data, offset)?),
+                offset: read_u32(&data, offset + 8)?,
+                compressed_size: read_u32(&data, offset + 12)?,
+                size: read_u32(&data, offset + 16)?,
+                compression,
+                subchunk_count,
+                duplicate: read_array::<1>(&data, offset + 21)?[0] != 0,
+                subchunk_index: read_u16(&data, offset + 22)?,
+                checksum: if entry_size >= 32 { read_u64(&data, offset + 24)? } else { 0 },
+            });
+        }
+        Ok(BinWad {
+            major,
+            minor,
+            chunks,
+            data,
+        })
+    }
+
+    pub fn read_from_path<P: AsRef<Path>>(path: P, hashes: &BinHashes) -> Result<BinWad> {
+        Self::read_from_data(std::fs::read(path)?, hashes)
+    }
+
+    // Only stored and gzip chunks are decoded; zstd and zstd-multi chunks report UnsupportedCompression.
+    pub fn chunk_data(&self, chunk: &BinWadChunk) -> Result<Vec<u8>> {
+        let start = chunk.offset as usize;
+        let raw = self
+            .data
+            .get(start..start + chunk.compressed_size as usize)
+            .ok_or(BinError::UnexpectedEof { offset: start as u64 })?;
+        match chunk.compression() {
+            Some(BinWadCompression::None) => Ok(raw.to_vec()),
+            Some(BinWadCompression::Gzip) => {
+                let mut result = Vec::with_capacity(chunk.size as usize);
+                GzDecoder::new(raw).read_to_end(&mut result)?;
+                Ok(result)
+            }
+            _ => Err(BinError::UnsupportedCompression {
+                path: chunk.path.get_hash(),
+                kind: chunk.compression,
+            }),
+        }
+    }
+
+    pub fn read_bin(&self, chunk: &BinWadChunk, hashes: &BinHashes) -> Result<Bin> {
+        Bin::read_from_data(&self.chunk_data(chunk)?, hashes)
+    }
+
+    pub fn read_bins(&self, hashes: &BinHashes) -> Vec<(&BinWadChunk, Result<Bin>)> {
+        let mut result = Vec::new();
+        for chunk in &self.chunks {
+            if chunk.is_bin() {
+                result.push((chunk, self.read_bin(chunk, hashes)));
+            } else if chunk.path.get_string().is_empty() {
+                let data = match self.chunk_data(chunk) {
+                    Ok(data) => data,
+                    Err(_) => continue,
+                };
+                if data.starts_with(b"PROP") || data.starts_with(b"PTCH") {
+                    result.push((chunk, Bin::read_from_data(&data, hashes)));
+                }
+            }
+        }
+        result
+    }
+}
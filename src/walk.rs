@@ -0,0 +1,100 @@
+use crate::*;
+use std::fmt::Display;
+
+#[derive(Clone, Debug)]
+pub enum BinPathSegment {
+    Entry(BinFNV),
+    Field(BinFNV),
+    Index(usize),
+    Key(String),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BinPath {
+    pub segments: Vec<BinPathSegment>,
+}
+
+fn name_label(name: &BinFNV) -> String {
+    if name.get_string().is_empty() {
+        format!("0x{:08X}", name.get_hash())
+    } else {
+        name.get_string().to_string()
+    }
+}
+
+impl Display for BinPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for segment in &self.segments {
+            match segment {
+                BinPathSegment::Entry(name) => write!(f, "{}", name_label(name))?,
+                BinPathSegment::Field(name) => write!(f, ".{}", name_label(name))?,
+                BinPathSegment::Index(index) => write!(f, "[{}]", index)?,
+                BinPathSegment::Key(key) => write!(f, "[{}]", key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn walk_in<F>(path: &mut BinPath, value: &BinValue, f: &mut F)
+where
+    F: FnMut(&BinPath, &BinValue),
+{
+    f(path, value);
+    match value {
+        BinValue::List(_, items) | BinValue::List2(_, items) => {
+            for (index, item) in items.iter().enumerate() {
+                path.segments.push(BinPathSegment::Index(index));
+                walk_in(path, item, f);
+                path.segments.pop();
+            }
+        }
+        BinValue::Option(_, Some(item)) => {
+            path.segments.push(BinPathSegment::Index(0));
+            walk_in(path, item, f);
+            path.segments.pop();
+        }
+        BinValue::Map(_, _, items) => {
+            for (key, value) in items {
+                path.segments.push(BinPathSegment::Key(key.to_string()));
+                walk_in(path, value, f);
+                path.segments.pop();
+            }
+        }
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+            let mut fields: Vec<(&BinFNV, &BinValue)> = fields.iter().collect();
+            fields.sort_by_key(|(name, _)| name.get_hash());
+            for (name, value) in fields {
+                path.segments.push(BinPathSegment::Field(*name));
+                walk_in(path, value, f);
+                path.segments.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+impl BinValue {
+    pub fn walk<F>(&self, mut f: F)
+    where
+        F: FnMut(&BinPath, &BinValue),
+    {
+        walk_in(&mut BinPath::default(), self, &mut f);
+    }
+}
+
+impl Bin {
+    pub fn walk<F>(&self, mut f: F)
+    where
+        F: FnMut(&BinPath, &BinValue),
+    {
+        let mut entries: Vec<(&BinFNV, &BinValue)> = self.entries.iter().collect();
+        entries.sort_by_key(|(name, _)| name.get_hash());
+        let mut path = BinPath::default();
+        for (name, value) in entries {
+            path.segments.push(BinPathSegment::Entry(*name));
+            walk_in(&mut path, value, &mut f);
+            path.segments.pop();
+        }
+    }
+}
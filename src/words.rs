@@ -0,0 +1,114 @@
+use crate::*;
+use std::collections::HashMap;
+
+fn split_camel_case(segment: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = segment.char_indices().collect();
+    let mut result = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (pos, cur) = chars[i];
+        let prev = chars[i - 1].1;
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        let boundary = (prev.is_lowercase() && cur.is_uppercase())
+            || (prev.is_alphabetic() && cur.is_ascii_digit())
+            || (prev.is_ascii_digit() && cur.is_alphabetic())
+            || (prev.is_uppercase()
+                && cur.is_uppercase()
+                && next.is_some_and(|c| c.is_lowercase()));
+        if boundary {
+            result.push(&segment[start..pos]);
+            start = pos;
+        }
+    }
+    if start < segment.len() {
+        result.push(&segment[start..]);
+    }
+    result
+}
+
+pub struct BinWordlist {
+    pub counts: HashMap<String, usize>,
+}
+
+impl BinWordlist {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+
+    fn add_word(&mut self, word: &str) {
+        if !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()) {
+            *self.counts.entry(word.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    pub fn add_string(&mut self, string: &str) {
+        for segment in string.split(|c: char| !c.is_alphanumeric()) {
+            if segment.is_empty() {
+                continue;
+            }
+            self.add_word(segment);
+            let words = split_camel_case(segment);
+            if words.len() > 1 {
+                for word in words {
+                    self.add_word(word);
+                }
+            }
+        }
+    }
+
+    pub fn add_value(&mut self, value: &BinValue) {
+        match value {
+            BinValue::String(value) => self.add_string(value),
+            BinValue::Hash(value) | BinValue::Link(value) => self.add_string(value.get_string()),
+            BinValue::File(value) => self.add_string(value.get_string()),
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                for item in items {
+                    self.add_value(item);
+                }
+            }
+            BinValue::Option(_, Some(item)) => self.add_value(item),
+            BinValue::Map(_, _, items) => {
+                for (key, value) in items {
+                    self.add_value(key);
+                    self.add_value(value);
+                }
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                self.add_string(name.get_string());
+                for (name, value) in fields {
+                    self.add_string(name.get_string());
+                    self.add_value(value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn add_bin(&mut self, bin: &Bin) {
+        for link in &bin.links {
+            self.add_string(link);
+        }
+        for (name, value) in &bin.entries {
+            self.add_string(name.get_string());
+            self.add_value(value);
+        }
+    }
+
+    pub fn ranked(&self) -> Vec<(String, usize)> {
+        let mut result: Vec<(String, usize)> = self
+            .counts
+            .iter()
+            .map(|(word, count)| (word.clone(), *count))
+            .collect();
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        result
+    }
+}
+
+impl Default for BinWordlist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
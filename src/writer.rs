@@ -0,0 +1,357 @@
+use crate::*;
+use std::io::Write;
+
+type Result<T> = std::result::Result<T, BinError>;
+
+pub struct BinWriter {
+    buf: Vec<u8>,
+}
+
+impl BinWriter {
+    fn write_u8(&mut self, value: u8) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_f32(&mut self, value: f32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_type(&mut self, bin_type: BinType) {
+        self.write_u8(bin_type as u8);
+    }
+
+    fn write_floats(&mut self, values: &[f32]) {
+        for value in values {
+            self.write_f32(*value);
+        }
+    }
+
+    fn write_mtx44(&mut self, value: &[[f32; 4]; 4]) {
+        for row in value {
+            self.write_floats(row);
+        }
+    }
+
+    fn write_rgba(&mut self, value: &[u8; 4]) {
+        self.write_u8(value[3]);
+        self.write_u8(value[2]);
+        self.write_u8(value[1]);
+        self.write_u8(value[0]);
+    }
+
+    fn write_string(&mut self, value: &str) -> Result<()> {
+        if value.len() > u16::MAX as usize {
+            return Err(BinError::StringTooLong { len: value.len() });
+        }
+        self.write_u16(value.len() as u16);
+        self.buf.extend_from_slice(value.as_bytes());
+        Ok(())
+    }
+
+    fn write_sub_writer<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        let len_pos = self.buf.len();
+        self.write_u32(0);
+        f(self)?;
+        let len = (self.buf.len() - len_pos - 4) as u32;
+        self.buf[len_pos..len_pos + 4].copy_from_slice(&len.to_le_bytes());
+        Ok(())
+    }
+
+    fn items_type<'v, I>(declared: BinType, items: I) -> BinType
+    where
+        I: Iterator<Item = &'v BinValue>,
+    {
+        match declared {
+            BinType::None => BinType::of_items(items),
+            _ => declared,
+        }
+    }
+
+    fn write_typed(&mut self, value: &BinValue, bin_type: BinType) -> Result<()> {
+        match value {
+            BinValue::Unknown(_, _, _) => Err(BinError::UnknownValue),
+            BinValue::None => match bin_type {
+                BinType::None => Ok(()),
+                BinType::Pointer | BinType::Embed => {
+                    self.write_u32(0);
+                    Ok(())
+                }
+                _ => Err(BinError::NullValue),
+            },
+            _ if value.bin_type() != bin_type => Err(BinError::MixedTypes),
+            _ => self.write_value(value),
+        }
+    }
+
+    fn write_fields(&mut self, fields: &IndexMap<BinFNV, BinValue>) -> Result<()> {
+        let trailing: usize = fields
+            .values()
+            .map(|value| match value {
+                BinValue::Unknown(_, _, trailing) => *trailing as usize,
+                _ => 0,
+            })
+            .sum();
+        let len = fields.len() + trailing;
+        if len > u16::MAX as usize {
+            return Err(BinError::TooManyFields { len });
+        }
+        self.write_u16(len as u16);
+        for (name, value) in fields {
+            if let BinValue::Unknown(_, _, _) = value {
+                continue;
+            }
+            self.write_u32(name.get_hash());
+            let value_type = value.bin_type();
+            self.write_type(value_type);
+            self.write_typed(value, value_type)?;
+        }
+        for (name, value) in fields {
+            if let BinValue::Unknown(type_id, bytes, _) = value {
+                self.write_u32(name.get_hash());
+                self.write_u8(*type_id);
+                self.buf.extend_from_slice(bytes);
+            }
+        }
+        Ok(())
+    }
+
+    fn write_value(&mut self, value: &BinValue) -> Result<()> {
+        let io = self;
+        match value {
+            BinValue::None => {}
+            BinValue::Unknown(_, _, _) => return Err(BinError::UnknownValue),
+            BinValue::Bool(value) | BinValue::Flag(value) => io.write_u8(*value as u8),
+            BinValue::I8(value) => io.buf.extend_from_slice(&value.to_le_bytes()),
+            BinValue::U8(value) => io.buf.extend_from_slice(&value.to_le_bytes()),
+            BinValue::I16(value) => io.buf.extend_from_slice(&value.to_le_bytes()),
+            BinValue::U16(value) => io.buf.extend_from_slice(&value.to_le_bytes()),
+            BinValue::I32(value) => io.buf.extend_from_slice(&value.to_le_bytes()),
+            BinValue::U32(value) => io.buf.extend_from_slice(&value.to_le_bytes()),
+            BinValue::I64(value) => io.buf.extend_from_slice(&value.to_le_bytes()),
+            BinValue::U64(value) => io.buf.extend_from_slice(&value.to_le_bytes()),
+            BinValue::Float(value) => io.write_f32(*value),
+            BinValue::Vec2(value) => io.write_floats(value),
+            BinValue::Vec3(value) => io.write_floats(value),
+            BinValue::Vec4(value) => io.write_floats(value),
+            BinValue::Mtx44(value) => io.write_mtx44(value),
+            BinValue::Rgba(value) => io.write_rgba(value),
+            BinValue::String(value) => io.write_string(value)?,
+            BinValue::Hash(value) | BinValue::Link(value) => io.write_u32(value.get_hash()),
+            BinValue::File(value) => io.write_u64(value.get_hash()),
+            BinValue::List(value_type, items) | BinValue::List2(value_type, items) => {
+                let value_type = Self::items_type(*value_type, items.iter());
+                io.write_type(value_type);
+                io.write_sub_writer(|io| {
+                    io.write_u32(items.len() as u32);
+                    for item in items {
+                        io.write_typed(item, value_type)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            BinValue::Option(value_type, value) => {
+                let value_type = Self::items_type(*value_type, value.as_deref().into_iter());
+                io.write_type(value_type);
+                io.write_u8(value.is_some() as u8);
+                if let Some(value) = value {
+                    io.write_typed(value, value_type)?;
+                }
+            }
+            BinValue::Map(key_type, value_type, items) => {
+                let key_type = Self::items_type(*key_type, items.iter().map(|(key, _)| key));
+                let value_type = Self::items_type(*value_type, items.iter().map(|(_, value)| value));
+                io.write_type(key_type);
+                io.write_type(value_type);
+                io.write_sub_writer(|io| {
+                    io.write_u32(items.len() as u32);
+                    for (key, value) in items {
+                        io.write_typed(key, key_type)?;
+                        io.write_typed(value, value_type)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            BinValue::Struct(name, fields) | BinValue::Embed(name, fields) => {
+                if name.get_hash() == 0 && !fields.is_empty() {
+                    return Err(BinError::NullValue);
+                }
+                io.write_u32(name.get_hash());
+                if name.get_hash() != 0 {
+                    io.write_sub_writer(|io| io.write_fields(fields))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_entry(&mut self, name: &BinFNV, value: &BinValue) -> Result<()> {
+        match value {
+            BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => self.write_sub_writer(|io| {
+                io.write_u32(name.get_hash());
+                io.write_fields(fields)
+            }),
+            _ => Err(BinError::EntryNotStruct),
+        }
+    }
+
+    fn write_entries(&mut self, entries: &IndexMap<BinFNV, BinValue>) -> Result<()> {
+        self.write_u32(entries.len() as u32);
+        for value in entries.values() {
+            match value {
+                BinValue::Struct(type_name, _) | BinValue::Embed(type_name, _) => {
+                    self.write_u32(type_name.get_hash())
+                }
+                _ => return Err(BinError::EntryNotStruct),
+            }
+        }
+        for (name, value) in entries {
+            self.write_entry(name, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_links(&mut self, links: &[String]) -> Result<()> {
+        self.write_u32(links.len() as u32);
+        for link in links {
+            self.write_string(link)?;
+        }
+        Ok(())
+    }
+
+    fn write_patch(&mut self, patch: &BinPatch) -> Result<()> {
+        self.write_u32(patch.name.get_hash());
+        self.write_sub_writer(|io| {
+            let value_type = patch.value.bin_type();
+            io.write_type(value_type);
+            io.write_string(&patch.path)?;
+            io.write_typed(&patch.value, value_type)
+        })
+    }
+
+    fn write_patches(&mut self, patches: &[BinPatch]) -> Result<()> {
+        self.write_u32(patches.len() as u32);
+        for patch in patches {
+            self.write_patch(patch)?;
+        }
+        Ok(())
+    }
+
+    fn write_header(&mut self, is_patch: bool, version: u32, links: &[String]) -> Result<()> {
+        if is_patch {
+            self.write_u32(0x48435450);
+            self.write_u64(1);
+        }
+        self.write_u32(0x504f5250);
+        self.write_u32(version);
+        self.write_links(links)
+    }
+
+    pub fn write_bin(bin: &Bin) -> Result<Vec<u8>> {
+        let mut writer = BinWriter { buf: Vec::new() };
+        writer.write_header(bin.is_patch, bin.version, &bin.links)?;
+        writer.write_entries(&bin.entries)?;
+        if bin.is_patch && bin.version >= 3 {
+            writer.write_patches(&bin.patches)?;
+        }
+        writer.buf.extend_from_slice(&bin.tail);
+        Ok(writer.buf)
+    }
+}
+
+pub struct BinStreamWriter<W: Write> {
+    out: W,
+    is_patch: bool,
+    version: u32,
+    links: Vec<String>,
+    type_names: Vec<u32>,
+    entries: BinWriter,
+    patches: BinWriter,
+    patch_count: u32,
+}
+
+impl<W: Write> BinStreamWriter<W> {
+    pub fn new(out: W, is_patch: bool, version: u32, links: Vec<String>) -> Self {
+        Self {
+            out,
+            is_patch,
+            version,
+            links,
+            type_names: Vec::new(),
+            entries: BinWriter { buf: Vec::new() },
+            patches: BinWriter { buf: Vec::new() },
+            patch_count: 0,
+        }
+    }
+
+    pub fn write_entry(&mut self, name: &BinFNV, value: &BinValue) -> Result<()> {
+        let type_name = match value {
+            BinValue::Struct(type_name, _) | BinValue::Embed(type_name, _) => type_name.get_hash(),
+            _ => return Err(BinError::EntryNotStruct),
+        };
+        let len = self.entries.buf.len();
+        if let Err(error) = self.entries.write_entry(name, value) {
+            self.entries.buf.truncate(len);
+            return Err(error);
+        }
+        self.type_names.push(type_name);
+        Ok(())
+    }
+
+    pub fn write_raw_entry(&mut self, type_name: &BinFNV, payload: &[u8]) -> Result<()> {
+        if payload.len() < 6 {
+            return Err(BinError::UnexpectedEof {
+                offset: payload.len() as u64,
+            });
+        }
+        self.entries.write_u32(payload.len() as u32);
+        self.entries.buf.extend_from_slice(payload);
+        self.type_names.push(type_name.get_hash());
+        Ok(())
+    }
+
+    pub fn write_patch(&mut self, patch: &BinPatch) -> Result<()> {
+        let len = self.patches.buf.len();
+        if let Err(error) = self.patches.write_patch(patch) {
+            self.patches.buf.truncate(len);
+            return Err(error);
+        }
+        self.patch_count += 1;
+        Ok(())
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.type_names.len()
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        let mut header = BinWriter { buf: Vec::new() };
+        header.write_header(self.is_patch, self.version, &self.links)?;
+        header.write_u32(self.type_names.len() as u32);
+        for type_name in &self.type_names {
+            header.write_u32(*type_name);
+        }
+        self.out.write_all(&header.buf)?;
+        self.out.write_all(&self.entries.buf)?;
+        if self.is_patch && self.version >= 3 {
+            self.out.write_all(&self.patch_count.to_le_bytes())?;
+            self.out.write_all(&self.patches.buf)?;
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
@@ -0,0 +1,260 @@
+use crate::*;
+use std::fmt::Write;
+
+fn escape_string(value: &str, out: &mut String) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn is_plain_key(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn write_tag_name(name: &BinFNV, out: &mut String) {
+    let name = name.get_string();
+    if name.is_empty() {
+        return;
+    }
+    out.push(':');
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'_' | b'.' | b'/' | b'-' => out.push(byte as char),
+            byte => write!(out, "%{:02X}", byte).unwrap(),
+        }
+    }
+}
+
+fn write_float(value: f32, out: &mut String) {
+    if value.is_nan() {
+        out.push_str(".nan");
+    } else if value.is_infinite() {
+        out.push_str(if value > 0.0 { ".inf" } else { "-.inf" });
+    } else {
+        write!(out, "{:?}", value).unwrap();
+    }
+}
+
+fn write_floats(values: &[f32], out: &mut String) {
+    out.push('[');
+    for (index, value) in values.iter().enumerate() {
+        if index != 0 {
+            out.push_str(", ");
+        }
+        write_float(*value, out);
+    }
+    out.push(']');
+}
+
+fn is_block(value: &BinValue) -> bool {
+    match value {
+        BinValue::List(_, items) | BinValue::List2(_, items) => !items.is_empty(),
+        BinValue::Option(_, value) => value.is_some(),
+        BinValue::Map(_, _, items) => !items.is_empty(),
+        BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => !fields.is_empty(),
+        _ => false,
+    }
+}
+
+struct YamlWriter {
+    out: String,
+    indent: usize,
+}
+
+impl YamlWriter {
+    fn write_indent(&mut self) {
+        for _ in 0..self.indent {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn write_name(&mut self, name: &BinFNV) {
+        if name.get_string().is_empty() {
+            write!(self.out, "0x{:08X}", name.get_hash()).unwrap();
+        } else {
+            escape_string(name.get_string(), &mut self.out);
+        }
+    }
+
+    fn write_key(&mut self, name: &BinFNV) {
+        if is_plain_key(name.get_string()) {
+            self.out.push_str(name.get_string());
+        } else if name.get_string().is_empty() {
+            write!(self.out, "\"0x{:08X}\"", name.get_hash()).unwrap();
+        } else {
+            escape_string(name.get_string(), &mut self.out);
+        }
+    }
+
+    fn write_children(&mut self, value: &BinValue) {
+        self.indent += 1;
+        match value {
+            BinValue::List(_, items) | BinValue::List2(_, items) => {
+                for item in items {
+                    self.out.push('\n');
+                    self.write_indent();
+                    self.out.push_str("- ");
+                    self.write_value(item);
+                }
+            }
+            BinValue::Option(_, Some(value)) => {
+                self.out.push('\n');
+                self.write_indent();
+                self.out.push_str("- ");
+                self.write_value(value);
+            }
+            BinValue::Map(_, _, items) => {
+                for (key, value) in items {
+                    self.out.push('\n');
+                    self.write_indent();
+                    self.write_value(key);
+                    self.out.push_str(": ");
+                    self.write_value(value);
+                }
+            }
+            BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => {
+                for (name, value) in fields {
+                    self.out.push('\n');
+                    self.write_indent();
+                    self.write_key(name);
+                    self.out.push_str(": ");
+                    self.write_value(value);
+                }
+            }
+            _ => {}
+        }
+        self.indent -= 1;
+    }
+
+    fn write_value(&mut self, value: &BinValue) {
+        self.out.push('!');
+        match value {
+            BinValue::Struct(name, _) | BinValue::Embed(name, _) => {
+                self.out.push_str(value.bin_type().name());
+                write_tag_name(name, &mut self.out);
+            }
+            BinValue::Unknown(type_id, _, _) => write!(self.out, "unknown:0x{:02X}", type_id).unwrap(),
+            _ => self.out.push_str(value.bin_type().name()),
+        }
+        if is_block(value) {
+            self.write_children(value);
+            return;
+        }
+        self.out.push(' ');
+        match value {
+            BinValue::None => self.out.push_str("null"),
+            BinValue::Bool(value) | BinValue::Flag(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::I8(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::U8(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::I16(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::U16(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::I32(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::U32(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::I64(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::U64(value) => write!(self.out, "{}", value).unwrap(),
+            BinValue::Float(value) => write_float(*value, &mut self.out),
+            BinValue::Vec2(value) => write_floats(value, &mut self.out),
+            BinValue::Vec3(value) => write_floats(value, &mut self.out),
+            BinValue::Vec4(value) => write_floats(value, &mut self.out),
+            BinValue::Mtx44(value) => {
+                self.out.push('[');
+                for (index, row) in value.iter().enumerate() {
+                    if index != 0 {
+                        self.out.push_str(", ");
+                    }
+                    write_floats(row, &mut self.out);
+                }
+                self.out.push(']');
+            }
+            BinValue::Rgba(value) => {
+                write!(self.out, "[{}, {}, {}, {}]", value[0], value[1], value[2], value[3]).unwrap()
+            }
+            BinValue::String(value) => escape_string(value, &mut self.out),
+            BinValue::Hash(name) | BinValue::Link(name) => self.write_name(name),
+            BinValue::File(file) if file.get_string().is_empty() => {
+                write!(self.out, "0x{:016X}", file.get_hash()).unwrap()
+            }
+            BinValue::File(file) => escape_string(file.get_string(), &mut self.out),
+            BinValue::List(_, _) | BinValue::List2(_, _) | BinValue::Option(_, _) => self.out.push_str("[]"),
+            BinValue::Map(_, _, _) | BinValue::Struct(..) | BinValue::Embed(..) => self.out.push_str("{}"),
+            BinValue::Unknown(_, bytes, _) => {
+                self.out.push('"');
+                for byte in bytes {
+                    write!(self.out, "{:02x}", byte).unwrap();
+                }
+                self.out.push('"');
+            }
+        }
+    }
+
+    fn write_bin(&mut self, bin: &Bin) {
+        writeln!(self.out, "type: {}", if bin.is_patch { "PTCH" } else { "PROP" }).unwrap();
+        writeln!(self.out, "version: {}", bin.version).unwrap();
+        if bin.links.is_empty() {
+            self.out.push_str("linked: []\n");
+        } else {
+            self.out.push_str("linked:\n");
+            for link in &bin.links {
+                self.out.push_str("  - ");
+                escape_string(link, &mut self.out);
+                self.out.push('\n');
+            }
+        }
+        if bin.entries.is_empty() {
+            self.out.push_str("entries: {}\n");
+        } else {
+            self.out.push_str("entries:");
+            self.indent = 1;
+            for (name, value) in &bin.entries {
+                self.out.push('\n');
+                self.write_indent();
+                self.write_key(name);
+                self.out.push_str(": ");
+                self.write_value(value);
+            }
+            self.indent = 0;
+            self.out.push('\n');
+        }
+        if bin.is_patch {
+            if bin.patches.is_empty() {
+                self.out.push_str("patches: []\n");
+            } else {
+                self.out.push_str("patches:");
+                self.indent = 2;
+                for patch in &bin.patches {
+                    self.out.push_str("\n  - entry: ");
+                    self.write_name(&patch.name);
+                    self.out.push_str("\n    path: ");
+                    escape_string(&patch.path, &mut self.out);
+                    self.out.push_str("\n    value: ");
+                    self.write_value(&patch.value);
+                }
+                self.indent = 0;
+                self.out.push('\n');
+            }
+        }
+    }
+}
+
+impl Bin {
+    pub fn to_yaml(&self) -> String {
+        let mut writer = YamlWriter {
+            out: String::new(),
+            indent: 0,
+        };
+        writer.write_bin(self);
+        writer.out
+    }
+}
@@ -0,0 +1,15 @@
+#![cfg(feature = "hash-lists")]
+
+use rbin::*;
+
+#[test]
+fn lanes_backend_matches_scalar() {
+    let mut names = vec![String::new(), "a".to_string(), "A".to_string()];
+    for index in 0..100 {
+        names.push("Characters/Hero/Skins/Skin".repeat(index % 7) + &index.to_string());
+    }
+    let expected: Vec<u32> = names.iter().map(|name| fnv(name)).collect();
+    assert_eq!(ScalarFnvBackend.fnv_many(&names), expected);
+    assert_eq!(LanesFnvBackend.fnv_many(&names), expected);
+    assert_eq!(fnv_many(&names), expected);
+}
@@ -0,0 +1,98 @@
+#![cfg(all(feature = "tools", feature = "serde"))]
+
+use rbin::*;
+use serde_json::Value;
+
+fn name(string: &str) -> BinFNV {
+    BinFNV::from_hash_string(fnv(string), string)
+}
+
+fn sample() -> Bin {
+    let mut inner = IndexMap::new();
+    inner.insert(name("count"), BinValue::U32(3));
+    let mut fields = IndexMap::new();
+    fields.insert(name("title"), BinValue::String("hello".into()));
+    fields.insert(
+        name("items"),
+        BinValue::List(BinType::Embed, vec![BinValue::Embed(name("Inner"), inner.clone())]),
+    );
+    fields.insert(
+        name("lookup"),
+        BinValue::Map(
+            BinType::Hash,
+            BinType::Pointer,
+            vec![(BinValue::Hash(name("key")), BinValue::Struct(name("Inner"), inner))],
+        ),
+    );
+    fields.insert(name("maybe"), BinValue::Option(BinType::F32, Some(Box::new(BinValue::Float(1.5)))));
+    fields.insert(name("link"), BinValue::Link(name("Other/Entry")));
+    let mut entries = IndexMap::new();
+    entries.insert(name("Some/Entry"), BinValue::Embed(name("Outer"), fields));
+    Bin {
+        is_patch: false,
+        version: 3,
+        links: Vec::new(),
+        entries,
+        patches: Vec::new(),
+        tail: Vec::new(),
+        errors: Vec::new(),
+    }
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn value_paths(path: &str, json: &Value, result: &mut Vec<(String, Value)>) {
+    if json.get("type").is_some_and(Value::is_string) {
+        result.push((path.to_string(), json.clone()));
+    }
+    match json {
+        Value::Object(object) => {
+            for (key, value) in object {
+                value_paths(&format!("{}/{}", path, escape(key)), value, result);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                value_paths(&format!("{}/{}", path, index), item, result);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn pointer_resolves_exported_paths() {
+    let bin = sample();
+    let json = bin.to_json();
+    let mut paths = Vec::new();
+    value_paths("", &json["entries"], &mut paths);
+    assert!(paths.len() >= 10);
+    for (path, value) in paths {
+        let pointer = format!("/entries{}", path);
+        let expected = BinValue::from_json(&value).unwrap();
+        assert_eq!(bin.pointer(&pointer), Some(&expected), "{}", pointer);
+    }
+}
+
+#[test]
+fn pointer_mut_resolves_exported_paths() {
+    let mut bin = sample();
+    *bin.pointer_mut("/entries/Some~1Entry/value/lookup/value/0/1/value/count").unwrap() = BinValue::U32(4);
+    assert_eq!(bin.pointer("/Some~1Entry/lookup/key/count"), Some(&BinValue::U32(4)));
+    assert_eq!(bin.pointer("/entries/Some~1Entry/value/maybe/value"), Some(&BinValue::Float(1.5)));
+    assert_eq!(bin.pointer("/entries/Some~1Entry/value/missing"), None);
+}
+
+#[test]
+fn pointer_resolves_validation_paths() {
+    let bin = sample();
+    let mut json = bin.to_json();
+    json["entries"]["Some/Entry"]["value"]["title"]["value"] = Value::from(7);
+    let errors = validate_json(&json);
+    assert_eq!(errors.len(), 1);
+    let (path, _) = &errors[0];
+    let parent = path.strip_suffix("/value").unwrap();
+    assert_eq!(bin.pointer(parent), Some(&BinValue::String("hello".into())));
+}
@@ -0,0 +1,207 @@
+use rbin::*;
+
+struct Fields {
+    count: u16,
+    data: Vec<u8>,
+}
+
+impl Fields {
+    fn new() -> Self {
+        Self { count: 0, data: Vec::new() }
+    }
+
+    fn field(mut self, name: &str, bin_type: u8, value: &[u8]) -> Self {
+        self.count += 1;
+        self.data.extend_from_slice(&fnv(name).to_le_bytes());
+        self.data.push(bin_type);
+        self.data.extend_from_slice(value);
+        self
+    }
+
+    fn raw(mut self, name: &str, bytes: &[u8]) -> Self {
+        self.count += 1;
+        self.data.extend_from_slice(&fnv(name).to_le_bytes());
+        self.data.extend_from_slice(bytes);
+        self
+    }
+}
+
+fn sized(data: &[u8]) -> Vec<u8> {
+    let mut result = (data.len() as u32).to_le_bytes().to_vec();
+    result.extend_from_slice(data);
+    result
+}
+
+fn prop(entry: &str, class: &str, fields: Fields) -> Vec<u8> {
+    let mut data = b"PROP".to_vec();
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&fnv(class).to_le_bytes());
+    let mut body = fnv(entry).to_le_bytes().to_vec();
+    body.extend_from_slice(&fields.count.to_le_bytes());
+    body.extend_from_slice(&fields.data);
+    data.extend_from_slice(&sized(&body));
+    data
+}
+
+fn assert_roundtrip(data: &[u8], options: &ReadOptions) -> Bin {
+    let bin = Bin::read_from_data_with_options(data, &BinHashes::new(), options).unwrap();
+    assert_eq!(bin.write_to_data().unwrap(), data);
+    bin
+}
+
+fn entry_field<'a>(bin: &'a Bin, entry: &str, field: &str) -> &'a BinValue {
+    match &bin.entries[&BinFNV::from_string(entry)] {
+        BinValue::Embed(_, fields) => &fields[&BinFNV::from_string(field)],
+        value => panic!("{:?} is not a struct", value),
+    }
+}
+
+#[test]
+fn unknown_recovery_roundtrip() {
+    let fields = Fields::new()
+        .field("before", BinType::U32 as u8, &7u32.to_le_bytes())
+        .raw("mystery", &[0x7F, 1, 2, 3])
+        .raw("after", &[BinType::U8 as u8, 9])
+        .raw("last", &[BinType::Bool as u8, 1]);
+    let data = prop("Entry", "Class", fields);
+    let options = ReadOptions {
+        recover_unknown: true,
+        ..ReadOptions::default()
+    };
+    let bin = assert_roundtrip(&data, &options);
+    match entry_field(&bin, "Entry", "mystery") {
+        BinValue::Unknown(type_id, bytes, trailing) => {
+            assert_eq!(*type_id, 0x7F);
+            assert_eq!(*trailing, 2);
+            assert_eq!(bytes.len(), 3 + 6 + 6);
+        }
+        value => panic!("expected unknown, got {:?}", value),
+    }
+    assert_eq!(entry_field(&bin, "Entry", "mystery").bin_type(), BinType::None);
+    assert_eq!(BinValue::Unknown(BinType::U8 as u8, vec![9], 0).bin_type(), BinType::U8);
+}
+
+fn typed_fields() -> Fields {
+    let mut option = vec![BinType::String as u8, 1];
+    option.extend_from_slice(&3u16.to_le_bytes());
+    option.extend_from_slice(b"abc");
+    let mut empty_list = vec![BinType::Hash as u8];
+    empty_list.extend_from_slice(&sized(&0u32.to_le_bytes()));
+    let mut empty_map = vec![BinType::U32 as u8, BinType::Pointer as u8];
+    empty_map.extend_from_slice(&sized(&0u32.to_le_bytes()));
+    Fields::new()
+        .field("filled", BinType::Option as u8, &option)
+        .field("empty", BinType::Option as u8, &[BinType::Vec3 as u8, 0])
+        .field("list", BinType::List as u8, &empty_list)
+        .field("list2", BinType::List2 as u8, &empty_list)
+        .field("map", BinType::Map as u8, &empty_map)
+}
+
+#[test]
+fn option_and_empty_containers_roundtrip() {
+    let data = prop("Entry", "Class", typed_fields());
+    let bin = assert_roundtrip(&data, &ReadOptions::default());
+    match entry_field(&bin, "Entry", "filled") {
+        BinValue::Option(BinType::String, Some(value)) => assert_eq!(**value, BinValue::String("abc".into())),
+        value => panic!("expected filled option, got {:?}", value),
+    }
+    assert_eq!(entry_field(&bin, "Entry", "empty"), &BinValue::Option(BinType::Vec3, None));
+    assert_eq!(entry_field(&bin, "Entry", "list"), &BinValue::List(BinType::Hash, Vec::new()));
+    assert_eq!(entry_field(&bin, "Entry", "map"), &BinValue::Map(BinType::U32, BinType::Pointer, Vec::new()));
+}
+
+
+fn null_fields() -> Fields {
+    Fields::new()
+        .field("pointer", BinType::Pointer as u8, &0u32.to_le_bytes())
+        .field("embed", BinType::Embed as u8, &0u32.to_le_bytes())
+        .field("nothing", BinType::None as u8, &[])
+}
+
+#[test]
+fn null_values_roundtrip() {
+    let data = prop("Entry", "Class", null_fields());
+    let bin = assert_roundtrip(&data, &ReadOptions::default());
+    for (field, bin_type) in [("pointer", BinType::Pointer), ("embed", BinType::Embed), ("nothing", BinType::None)] {
+        let value = entry_field(&bin, "Entry", field);
+        assert!(value.is_null());
+        assert_eq!(value.bin_type(), bin_type);
+    }
+    let mut bin = bin;
+    if let BinValue::Struct(_, fields) | BinValue::Embed(_, fields) = &mut bin.entries[0] {
+        let null = BinValue::Struct(BinFNV::from_hash(0), fields.clone());
+        fields.insert(BinFNV::from_hash(fnv("pointer")), null);
+    }
+    assert!(matches!(bin.write_to_data(), Err(BinError::NullValue)));
+}
+
+#[cfg(feature = "tools")]
+#[test]
+fn text_roundtrip() {
+    let fields = typed_fields()
+        .field("null_pointer", BinType::Pointer as u8, &0u32.to_le_bytes())
+        .field("null_embed", BinType::Embed as u8, &0u32.to_le_bytes())
+        .field("nothing", BinType::None as u8, &[])
+        .field("name", BinType::String as u8, b"\x02\x00hi")
+        .raw("mystery", &[0x7F, 1, 2, 3])
+        .raw("after", &[BinType::U8 as u8, 9]);
+    let data = prop("Entry", "Class", fields);
+    let options = ReadOptions {
+        recover_unknown: true,
+        ..ReadOptions::default()
+    };
+    let bin = assert_roundtrip(&data, &options);
+    let text = bin.to_text(&BinHashes::new());
+    let parsed = Bin::read_from_text(&text).unwrap();
+    assert_eq!(parsed.write_to_data().unwrap(), data);
+    assert_eq!(parsed.to_text(&BinHashes::new()), text);
+}
+
+#[cfg(all(feature = "serde", feature = "tools"))]
+#[test]
+fn json_roundtrip() {
+    let fields = typed_fields()
+        .field("null_pointer", BinType::Pointer as u8, &0u32.to_le_bytes())
+        .field("null_embed", BinType::Embed as u8, &0u32.to_le_bytes())
+        .field("nothing", BinType::None as u8, &[])
+        .field("name", BinType::String as u8, b"\x02\x00hi")
+        .raw("mystery", &[0x7F, 1, 2, 3])
+        .raw("after", &[BinType::U8 as u8, 9]);
+    let data = prop("Entry", "Class", fields);
+    let options = ReadOptions {
+        recover_unknown: true,
+        ..ReadOptions::default()
+    };
+    let bin = assert_roundtrip(&data, &options);
+    let json = bin.write_to_json();
+    let parsed = Bin::read_from_json(&json).unwrap();
+    let field_names = |bin: &Bin| -> Vec<u32> {
+        match &bin.entries[0] {
+            BinValue::Struct(_, fields) | BinValue::Embed(_, fields) => fields.keys().map(|name| name.get_hash()).collect(),
+            _ => Vec::new(),
+        }
+    };
+    assert_eq!(field_names(&parsed), field_names(&bin));
+    let written = parsed.write_to_data().unwrap();
+    assert_eq!(written, data);
+    let reread = Bin::read_from_data_with_options(&written, &BinHashes::new(), &options).unwrap();
+    assert_eq!(reread.entries, bin.entries);
+    assert_eq!(parsed.to_json(), bin.to_json());
+}
+
+#[cfg(all(feature = "serde", feature = "tools"))]
+#[test]
+fn json_import_error_path() {
+    let bin = assert_roundtrip(&prop("Entry", "Class", typed_fields()), &ReadOptions::default());
+    let mut json = bin.to_json();
+    let (entry, field) = (format!("0x{:08X}", fnv("Entry")), format!("0x{:08X}", fnv("filled")));
+    json["entries"][&entry]["value"][&field]["value"]["value"] = serde_json::Value::from(7);
+    match Bin::read_from_json(&json.to_string()) {
+        Err(BinError::Json { path, .. }) => {
+            assert_eq!(path, format!("/entries/{}/value/{}/value/value", entry, field))
+        }
+        result => panic!("expected a JSON error, got {:?}", result.map(|_| ())),
+    }
+}